@@ -0,0 +1,92 @@
+use crate::{AStar, Direction, PathGenerator};
+use std::collections::HashSet;
+
+// heuristic toward a cell next to goal, lowered by the largest estimate of any such cell
+struct AdjacentHeuristic<'a, T> {
+    inner: &'a T,
+    slack: usize,
+}
+
+impl<T: PathGenerator> PathGenerator for AdjacentHeuristic<'_, T> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner.generate_paths(from_position)
+    }
+
+    fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> usize {
+        self.inner.calculate_cost(current_position, next_position)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> usize {
+        self.inner
+            .calculate_heuristic_cost(position, target)
+            .saturating_sub(self.slack)
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: usize) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+impl AStar {
+    // reaches any cell goal can be entered from, goal itself may be impassable,
+    // neighbours are expected to be symmetric so they are taken from generate_paths(goal).
+    // approach_direction is the step from the reached cell toward goal
+    pub fn run_adjacent_to<T: PathGenerator>(
+        from_struct: &T,
+        start: (usize, usize),
+        goal: (usize, usize),
+    ) -> Option<(Vec<(usize, usize)>, Direction)> {
+        let target = (Some(goal.0), Some(goal.1));
+        let adjacent: HashSet<(usize, usize)> =
+            from_struct.generate_paths(goal).into_iter().collect();
+        let slack = adjacent
+            .iter()
+            .map(|position| from_struct.calculate_heuristic_cost(*position, target))
+            .max()?;
+        let wrapped = AdjacentHeuristic {
+            inner: from_struct,
+            slack,
+        };
+        let mut inst = Self::new(target);
+        let reached = inst.search_with(
+            &wrapped,
+            start,
+            || 0,
+            |node| adjacent.contains(&node.position),
+        )?;
+        let approach_direction = (
+            goal.0 as i64 - reached.position.0 as i64,
+            goal.1 as i64 - reached.position.1 as i64,
+        );
+        Some((Self::reconstruct_path(reached), approach_direction))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_approach_direction() {
+        // a door at (2, 2) only open toward the south
+        let map = GridMap::builder(5, 5)
+            .block_all([(2, 2), (1, 2), (3, 2), (2, 1)])
+            .build();
+        let (path, approach_direction) = AStar::run_adjacent_to(&map, (2, 0), (2, 2)).unwrap();
+        assert_eq!(path.first(), Some(&(2, 3)));
+        assert_eq!(approach_direction, (0, -1));
+        assert_eq!(path.len(), 8);
+
+        let (path, approach_direction) = AStar::run_adjacent_to(&map, (0, 0), (0, 2)).unwrap();
+        assert_eq!(path, vec![(0, 1), (0, 0)]);
+        assert_eq!(approach_direction, (0, 1));
+    }
+}
@@ -0,0 +1,251 @@
+use crate::{AStar, Cost, PathGenerator, PathResult, SearchStats};
+use std::collections::{HashMap, HashSet};
+
+// anytime weighted A*, every call to next lowers the weight by step and repairs the last
+// search instead of restarting it, the costs never increase and the one found at weight 1 is optimal
+pub struct AraStar<'a, T> {
+    from_struct: &'a T,
+    target: (Option<usize>, Option<usize>),
+    weight: f64,
+    step: f64,
+    started: bool,
+    costs: HashMap<(usize, usize), usize>,
+    comes_from: HashMap<(usize, usize), (usize, usize)>,
+    open: HashSet<(usize, usize)>,
+    closed: HashSet<(usize, usize)>,
+    inconsistent: HashSet<(usize, usize)>,
+    goal: Option<(usize, usize)>,
+    expanded: usize,
+}
+
+impl<'a, T: PathGenerator> AraStar<'a, T> {
+    pub fn new(
+        from_struct: &'a T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        initial_weight: f64,
+        step: f64,
+    ) -> Self {
+        assert!(initial_weight >= 1.0, "the weight can't start below 1");
+        assert!(step > 0.0, "the weight step has to be positive");
+        let mut ara = Self {
+            from_struct,
+            target,
+            weight: initial_weight,
+            step,
+            started: false,
+            costs: HashMap::from([(start, 0)]),
+            comes_from: HashMap::new(),
+            open: HashSet::from([start]),
+            closed: HashSet::new(),
+            inconsistent: HashSet::new(),
+            goal: None,
+            expanded: 0,
+        };
+        ara.update_goal(start);
+        ara
+    }
+
+    // weight the last returned path was found with
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+
+    fn key(&self, position: (usize, usize)) -> f64 {
+        let heuristic = self
+            .from_struct
+            .calculate_heuristic_cost(position, self.target);
+        self.costs[&position] as f64 + self.weight * heuristic as f64
+    }
+
+    fn goal_cost(&self) -> f64 {
+        self.goal
+            .map_or(f64::INFINITY, |goal| self.costs[&goal] as f64)
+    }
+
+    fn update_goal(&mut self, position: (usize, usize)) {
+        if AStar::<usize>::target_is_reached(self.target, &position)
+            && self.costs[&position] as f64 <= self.goal_cost()
+        {
+            self.goal = Some(position);
+        }
+    }
+
+    fn improve_path(&mut self) {
+        loop {
+            let Some((position, key)) = self
+                .open
+                .iter()
+                .map(|position| (*position, self.key(*position)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+            else {
+                return;
+            };
+            if key >= self.goal_cost() {
+                return;
+            }
+            self.open.remove(&position);
+            self.closed.insert(position);
+            self.expanded += 1;
+            let cost = self.costs[&position];
+            for possible_path in self.from_struct.generate_paths(position) {
+                let edge_cost = self.from_struct.calculate_cost(position, possible_path);
+                if edge_cost.is_infinite() {
+                    continue;
+                }
+                let new_cost = cost + edge_cost;
+                if self
+                    .costs
+                    .get(&possible_path)
+                    .is_some_and(|known| *known <= new_cost)
+                {
+                    continue;
+                }
+                self.costs.insert(possible_path, new_cost);
+                self.comes_from.insert(possible_path, position);
+                self.update_goal(possible_path);
+                if self.closed.contains(&possible_path) {
+                    self.inconsistent.insert(possible_path);
+                } else {
+                    self.open.insert(possible_path);
+                }
+            }
+        }
+    }
+
+    fn result(&self) -> Option<PathResult> {
+        let goal = self.goal?;
+        let mut path = vec![goal];
+        while let Some(previous) = self.comes_from.get(path.last().unwrap()) {
+            path.push(*previous);
+        }
+        // expanded counts every iteration so far, the other counters aren't tracked
+        let stats = SearchStats {
+            expanded: self.expanded,
+            path_len: path.len() - 1,
+            ..SearchStats::default()
+        };
+        Some(PathResult {
+            path,
+            cost: self.costs[&goal],
+            stats,
+            optimal: self.weight <= 1.0,
+            seed: None,
+        })
+    }
+}
+
+// why run_until_converged stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Convergence {
+    // the last patience improvements were each below epsilon, the result may not be optimal
+    Converged,
+    // the weight reached 1
+    Optimal,
+}
+
+impl<T: PathGenerator> AraStar<'_, T> {
+    // keeps lowering the weight until the cost improved by less than epsilon (relative to the
+    // previous cost) patience times in a row, None when the target can't be reached
+    pub fn run_until_converged(
+        &mut self,
+        epsilon: f64,
+        patience: usize,
+    ) -> Option<(PathResult, Convergence)> {
+        let mut best = self.next()?;
+        let mut stalled = 0;
+        while let Some(result) = self.next() {
+            let improvement = (best.cost - result.cost) as f64 / best.cost.max(1) as f64;
+            best = result;
+            if improvement >= epsilon {
+                stalled = 0;
+                continue;
+            }
+            stalled += 1;
+            if stalled >= patience && self.weight > 1.0 {
+                return Some((best, Convergence::Converged));
+            }
+        }
+        Some((best, Convergence::Optimal))
+    }
+}
+
+impl<T: PathGenerator> Iterator for AraStar<'_, T> {
+    type Item = PathResult;
+
+    fn next(&mut self) -> Option<PathResult> {
+        if self.started {
+            if self.weight <= 1.0 {
+                return None;
+            }
+            self.weight = (self.weight - self.step).max(1.0);
+            self.open.extend(self.inconsistent.drain());
+            self.closed.clear();
+        }
+        self.started = true;
+        self.improve_path();
+        let result = self.result();
+        if result.is_none() {
+            // nothing is reachable, lower weights won't change that
+            self.weight = 1.0;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::Graph;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_costs_improve_toward_optimal() {
+        // the heavy weight rushes through 1, the cheaper route over 2 looks expensive until w = 1
+        let mut graph = Graph::new(vec![(0, 1, 1), (1, 3, 10), (0, 2, 4), (2, 3, 4)]);
+        graph.heuristic = HashMap::from([(0, 5), (1, 1), (2, 4)]);
+        let target = (Some(3), Some(0));
+        let mut ara = AraStar::new(&graph, (0, 0), target, 3.0, 1.0);
+        let mut costs = Vec::new();
+        while let Some(result) = ara.next() {
+            assert_eq!(result.path.first(), Some(&(3, 0)));
+            assert_eq!(result.optimal, ara.weight() == 1.0);
+            costs.push((ara.weight(), result.cost));
+        }
+        assert_eq!(costs, vec![(3.0, 11), (2.0, 11), (1.0, 8)]);
+        assert!(costs.windows(2).all(|pair| pair[1].1 <= pair[0].1));
+        let optimal = AStar::run_result(&graph, (0, 0), target).unwrap();
+        assert_eq!(costs.last().unwrap().1, optimal.cost);
+    }
+
+    #[test]
+    fn test_unreachable_target() {
+        let graph = Graph::new(vec![(0, 1, 1)]);
+        let mut ara = AraStar::new(&graph, (0, 0), (Some(2), Some(0)), 2.0, 0.5);
+        assert_eq!(ara.next(), None);
+        assert_eq!(ara.next(), None);
+    }
+
+    #[test]
+    fn test_run_until_converged() {
+        let mut graph = Graph::new(vec![(0, 1, 1), (1, 3, 10), (0, 2, 4), (2, 3, 4)]);
+        graph.heuristic = HashMap::from([(0, 5), (1, 1), (2, 4)]);
+        let target = (Some(3), Some(0));
+        // 5, 4 and 3 all find the cost 11 route
+        let mut ara = AraStar::new(&graph, (0, 0), target, 5.0, 1.0);
+        let (result, convergence) = ara.run_until_converged(0.01, 2).unwrap();
+        assert_eq!((result.cost, convergence), (11, Convergence::Converged));
+        assert_eq!(ara.weight(), 3.0);
+
+        let mut ara = AraStar::new(&graph, (0, 0), target, 5.0, 1.0);
+        let (result, convergence) = ara.run_until_converged(0.01, 4).unwrap();
+        assert_eq!((result.cost, convergence), (8, Convergence::Optimal));
+    }
+
+    #[test]
+    fn test_infinite_edge_is_skipped() {
+        let graph = Graph::new(vec![(0, 1, usize::MAX), (0, 2, 1)]);
+        let mut ara = AraStar::new(&graph, (0, 0), (Some(1), Some(0)), 2.0, 1.0);
+        assert_eq!(ara.next(), None);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{AStar, Cost, Node};
+use std::rc::Rc;
+
+// PathGenerator for maps whose lookups have to be awaited, e.g. tiles from a database.
+// The search keeps Rc nodes across awaits, so its future isn't Send and neither are these
+#[allow(async_fn_in_trait)]
+pub trait AsyncPathGenerator<C: Cost = usize> {
+    async fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)>;
+    async fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C;
+    async fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> C;
+}
+
+impl<C: Cost> AStar<C> {
+    // same search as run, one lookup is awaited at a time
+    pub async fn run_async<T: AsyncPathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> Option<Vec<(usize, usize)>> {
+        let mut inst = Self::new(target);
+        let heuristic_cost = from_struct.calculate_heuristic_cost(start, target).await;
+        inst.que.push(Node::new(start, heuristic_cost));
+        while !inst.que.is_empty() {
+            inst.que.sort();
+            let top = Rc::new(inst.que.remove(0));
+            if inst.pull_from_closed_by_position(top.position).is_some() {
+                continue;
+            }
+            if Self::target_is_reached(target, &top.position) {
+                return Some(Self::reconstruct_path(top));
+            }
+            for possible_path in from_struct.generate_paths(top.position).await {
+                if inst.pull_from_closed_by_position(possible_path).is_some() {
+                    continue;
+                }
+                let cost = from_struct
+                    .calculate_cost(top.position, possible_path)
+                    .await;
+                if cost.is_infinite() {
+                    continue;
+                }
+                let heuristic_cost = from_struct
+                    .calculate_heuristic_cost(possible_path, target)
+                    .await;
+                let node =
+                    inst.create_new_node(Rc::clone(&top), possible_path, cost, heuristic_cost);
+                inst.que.push(node);
+            }
+            inst.closed_set.insert(top.position);
+            inst.closed_nodes.push(top);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::Graph;
+    use crate::{GridMap, PathGenerator};
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    // answers from a map after yielding once, like a lookup that isn't ready right away
+    struct Remote<T>(T);
+
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    impl<T: PathGenerator> AsyncPathGenerator for Remote<T> {
+        async fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            YieldOnce(false).await;
+            self.0.generate_paths(from_position)
+        }
+
+        async fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            YieldOnce(false).await;
+            self.0.calculate_heuristic_cost(position, target)
+        }
+
+        async fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            YieldOnce(false).await;
+            self.0.calculate_cost(current_position, next_position)
+        }
+    }
+
+    // block_on polls in a loop anyway, waking does nothing
+    struct NoopWake;
+
+    impl Wake for NoopWake {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn test_async_matches_sync() {
+        let map = GridMap::builder(6, 5)
+            .block_all([(2, 0), (2, 1), (2, 2), (2, 3), (4, 4), (4, 3)])
+            .build();
+        let target = (Some(5), Some(0));
+        let path = AStar::run(&map, (0, 0), target);
+        let remote = Remote(map);
+        assert_eq!(block_on(AStar::run_async(&remote, (0, 0), target)), path);
+        assert_eq!(
+            block_on(AStar::run_async(&remote, (0, 0), (Some(9), Some(9)))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_infinite_edge_is_skipped() {
+        let remote = Remote(Graph::new(vec![(0, 1, usize::MAX), (0, 2, 1)]));
+        assert_eq!(
+            block_on(AStar::run_async(&remote, (0, 0), (Some(1), Some(0)))),
+            None
+        );
+    }
+}
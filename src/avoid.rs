@@ -0,0 +1,146 @@
+use crate::{AStar, Cost, PathGenerator};
+use std::collections::HashSet;
+
+// drops avoided cells from the neighbours unless they reach the target
+struct AvoidInterior<'a, T> {
+    inner: &'a T,
+    avoid: &'a HashSet<(usize, usize)>,
+    target: (Option<usize>, Option<usize>),
+}
+
+impl<C: Cost, T: PathGenerator<C>> PathGenerator<C> for AvoidInterior<'_, T> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut possible_paths = self.inner.generate_paths(from_position);
+        possible_paths.retain(|position| {
+            !self.avoid.contains(position) || AStar::<C>::target_is_reached(self.target, position)
+        });
+        possible_paths
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.inner.calculate_cost(current_position, next_position)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        self.inner.calculate_heuristic_cost(position, target)
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+// inner without the cells allow rejects
+struct Filtered<'a, T, F> {
+    inner: &'a T,
+    allow: F,
+}
+
+impl<C: Cost, T: PathGenerator<C>, F: Fn((usize, usize)) -> bool> PathGenerator<C>
+    for Filtered<'_, T, F>
+{
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut possible_paths = self.inner.generate_paths(from_position);
+        possible_paths.retain(|position| (self.allow)(*position));
+        possible_paths
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.inner.calculate_cost(current_position, next_position)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        self.inner.calculate_heuristic_cost(position, target)
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+impl<C: Cost> AStar<C> {
+    // avoid cells can't be walked through, the path may still start or end on one
+    pub fn run_avoid_interior<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        avoid: &HashSet<(usize, usize)>,
+    ) -> Option<Vec<(usize, usize)>> {
+        let wrapped = AvoidInterior {
+            inner: from_struct,
+            avoid,
+            target,
+        };
+        AStar::run(&wrapped, start, target)
+    }
+
+    // cells allow rejects are impassable on top of what from_struct forbids, including start
+    pub fn run_filtered<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        allow: impl Fn((usize, usize)) -> bool,
+    ) -> Option<Vec<(usize, usize)>> {
+        if !allow(start) {
+            return None;
+        }
+        let wrapped = Filtered {
+            inner: from_struct,
+            allow,
+        };
+        AStar::run(&wrapped, start, target)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AStar, GridMap};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_avoid_interior() {
+        let map = GridMap::new(5, 2);
+        let avoid: HashSet<(usize, usize)> = [(0, 0), (2, 0), (4, 0)].into_iter().collect();
+
+        let path = AStar::run_avoid_interior(&map, (0, 0), (Some(4), Some(0)), &avoid).unwrap();
+        assert_eq!(path.first(), Some(&(4, 0)));
+        assert_eq!(path.last(), Some(&(0, 0)));
+        assert!(!path.contains(&(2, 0)));
+        assert_eq!(path.len(), 7);
+
+        // any avoided cell matching a partial target can be the goal
+        let path = AStar::run_avoid_interior(&map, (1, 0), (Some(2), None), &avoid).unwrap();
+        assert_eq!(path, vec![(2, 0), (1, 0)]);
+        let path = AStar::run_avoid_interior(&map, (1, 0), (Some(0), Some(1)), &avoid).unwrap();
+        assert_eq!(path, vec![(0, 1), (1, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn test_run_filtered() {
+        // only the left half and the bottom row were scouted
+        let map = GridMap::builder(8, 4).block((2, 3)).build();
+        let scouted = |position: (usize, usize)| position.0 < 4 || position.1 == 0;
+        let path = AStar::run_filtered(&map, (0, 3), (Some(7), Some(0)), scouted).unwrap();
+        assert!(path.iter().all(|position| scouted(*position)));
+        assert_eq!(path.len(), 11);
+        assert_eq!(
+            AStar::run(&map, (0, 3), (Some(7), Some(0))).unwrap().len(),
+            11
+        );
+
+        assert!(AStar::run_filtered(&map, (0, 3), (Some(7), Some(3)), scouted).is_none());
+        assert!(AStar::run_filtered(&map, (6, 3), (Some(7), Some(0)), scouted).is_none());
+        assert!(
+            AStar::run_filtered(&map, (3, 3), (Some(7), Some(0)), |position| position.0 < 4)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{AStar, Cost, PathGenerator};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+// remembers the heuristic of every position, only valid for a single target
+struct CachedHeuristic<'a, T, C> {
+    inner: &'a T,
+    cache: RefCell<HashMap<(usize, usize), C>>,
+}
+
+impl<C: Cost, T: PathGenerator<C>> PathGenerator<C> for CachedHeuristic<'_, T, C> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner.generate_paths(from_position)
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.inner.calculate_cost(current_position, next_position)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        *self
+            .cache
+            .borrow_mut()
+            .entry(position)
+            .or_insert_with(|| self.inner.calculate_heuristic_cost(position, target))
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+impl<C: Cost> AStar<C> {
+    // one run per start toward goal, in the order of starts, the heuristic of a position is
+    // calculated once for the whole batch
+    pub fn run_batch_same_goal<T: PathGenerator<C>>(
+        from_struct: &T,
+        starts: &[(usize, usize)],
+        goal: (usize, usize),
+    ) -> Vec<Option<Vec<(usize, usize)>>> {
+        let cached = CachedHeuristic {
+            inner: from_struct,
+            cache: RefCell::new(HashMap::new()),
+        };
+        starts
+            .iter()
+            .map(|start| AStar::run(&cached, *start, (Some(goal.0), Some(goal.1))))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AStar, GridMap, PathGenerator};
+    use std::cell::Cell;
+
+    // counts calculate_heuristic_cost calls
+    struct CountedHeuristic(GridMap, Cell<usize>);
+
+    impl PathGenerator for CountedHeuristic {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.0.generate_paths(from_position)
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            self.0.calculate_cost(current_position, next_position)
+        }
+
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            self.1.set(self.1.get() + 1);
+            self.0.calculate_heuristic_cost(position, target)
+        }
+    }
+
+    #[test]
+    fn test_run_batch_same_goal() {
+        let map = CountedHeuristic(
+            GridMap::builder(8, 8)
+                .block_all([(4, 2), (4, 3), (4, 4), (4, 5)])
+                .build(),
+            Cell::new(0),
+        );
+        let goal = (7, 4);
+        let starts = [(0, 4), (0, 3), (1, 4), (0, 0), (0, 7)];
+        let single: Vec<_> = starts
+            .iter()
+            .map(|start| AStar::run(&map, *start, (Some(goal.0), Some(goal.1))))
+            .collect();
+        let single_calls = map.1.replace(0);
+
+        assert_eq!(AStar::run_batch_same_goal(&map, &starts, goal), single);
+        assert!(map.1.get() < single_calls);
+        assert!(map.1.get() <= 64);
+    }
+}
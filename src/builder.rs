@@ -0,0 +1,465 @@
+use crate::{AStar, Node, PathError, PathGenerator, SearchStats, TieBreak};
+use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+pub struct AStarBuilder {
+    reopen_threshold: usize,
+    allow_reopening: bool,
+    max_closed: Option<usize>,
+    edge_time_budget: Option<Duration>,
+    dense_closed_set: Option<(usize, usize)>,
+    tie_break: TieBreak,
+    timeout: Option<Duration>,
+    clock: fn() -> Instant,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    max_path_len: Option<usize>,
+    action_cost: usize,
+}
+
+impl AStarBuilder {
+    // a closed node is reopened only when a path at least threshold cheaper reaches it,
+    // a threshold of 0 is treated as 1 so equal cost paths never reopen
+    pub fn reopen_threshold(mut self, threshold: usize) -> Self {
+        self.reopen_threshold = threshold;
+        self
+    }
+
+    // with false closed cells are skipped before their edge costs are calculated, which is
+    // only optimal when the heuristic is consistent: h(a) <= cost(a, b) + h(b) for every edge
+    pub fn allow_reopening(mut self, allow_reopening: bool) -> Self {
+        self.allow_reopening = allow_reopening;
+        self
+    }
+
+    // bounds memory by forgetting the oldest expanded nodes, forgotten cells can be expanded
+    // again so searches get slower, and one toward an unreachable target may never end
+    pub fn max_closed(mut self, max_closed: usize) -> Self {
+        self.max_closed = Some(max_closed);
+        self
+    }
+
+    // edges whose calculate_cost takes longer than budget are treated as impassable, the
+    // search then may miss the optimal or any path. run_with_stats counts them in
+    // SearchStats::slow_edges, run and try_run don't tell whether any were skipped
+    pub fn edge_time_budget(mut self, budget: Duration) -> Self {
+        self.edge_time_budget = Some(budget);
+        self
+    }
+
+    // keeps the expanded cells of a width x height grid in an array by y * width + x, so they
+    // are found without going through the closed nodes. Cells outside of it are closed as
+    // usual, max_closed only forgets those
+    pub fn dense_closed_set(mut self, width: usize, height: usize) -> Self {
+        self.dense_closed_set = Some((width, height));
+        self
+    }
+
+    pub fn tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    // both stop the search without a path, try_run tells which one did
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    // where edge_time_budget and timeout read the time, Instant::now unless replaced, e.g. by
+    // a clock a test moves forward itself
+    pub fn clock(mut self, clock: fn() -> Instant) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn cancel_flag(mut self, cancel_flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(cancel_flag);
+        self
+    }
+
+    // paths of more than max_path_len cells are dropped instead of built, run gives None
+    // and try_run PathError::PathTooLong
+    pub fn max_path_len(mut self, max_path_len: usize) -> Self {
+        self.max_path_len = Some(max_path_len);
+        self
+    }
+
+    // a fixed cost every step pays on top of calculate_cost, so paths of fewer steps win more
+    // often, the heuristic of the map stays admissible
+    pub fn action_cost(mut self, action_cost: usize) -> Self {
+        self.action_cost = action_cost;
+        self
+    }
+
+    pub fn run<T: PathGenerator>(
+        &self,
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> Option<Vec<(usize, usize)>> {
+        self.run_with_stats(from_struct, start, target).0
+    }
+
+    pub fn run_with_stats<T: PathGenerator>(
+        &self,
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> (Option<Vec<(usize, usize)>>, SearchStats) {
+        let mut inst = self.instance(target);
+        let path = inst
+            .search(from_struct, start)
+            .and_then(|goal| self.reconstruct(goal).ok());
+        let stats = inst.stats(path.as_ref());
+        (path, stats)
+    }
+
+    pub fn try_run<T: PathGenerator>(
+        &self,
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> Result<Vec<(usize, usize)>, PathError> {
+        let mut inst = self.instance(target);
+        match inst.search(from_struct, start) {
+            Some(goal) => self.reconstruct(goal),
+            None => Err(inst.error.unwrap_or(PathError::Unreachable)),
+        }
+    }
+
+    fn reconstruct(&self, goal: Rc<Node<usize>>) -> Result<Vec<(usize, usize)>, PathError> {
+        match self.max_path_len {
+            Some(max_path_len) => AStar::reconstruct_path_capped(goal, max_path_len)
+                .ok_or(PathError::PathTooLong(max_path_len)),
+            None => Ok(AStar::reconstruct_path(goal)),
+        }
+    }
+
+    fn instance(&self, target: (Option<usize>, Option<usize>)) -> AStar {
+        let mut inst = AStar::new(target);
+        inst.reopen_threshold = self.allow_reopening.then_some(self.reopen_threshold.max(1));
+        inst.max_closed = self.max_closed;
+        inst.action_cost = self.action_cost;
+        inst.edge_time_budget = self.edge_time_budget;
+        inst.tie_break_mode = self.tie_break;
+        inst.timeout = self.timeout;
+        inst.clock = self.clock;
+        inst.cancel_flag = self.cancel_flag.clone();
+        if let Some((width, height)) = self.dense_closed_set {
+            inst.dense_grid = Some((width, height));
+            inst.closed_slots.resize_with(width * height, || None);
+        }
+        inst
+    }
+}
+
+impl Default for AStarBuilder {
+    fn default() -> Self {
+        Self {
+            reopen_threshold: 1,
+            allow_reopening: true,
+            max_closed: None,
+            edge_time_budget: None,
+            dense_closed_set: None,
+            tie_break: TieBreak::default(),
+            timeout: None,
+            clock: Instant::now,
+            cancel_flag: None,
+            max_path_len: None,
+            action_cost: 0,
+        }
+    }
+}
+
+impl AStar {
+    pub fn builder() -> AStarBuilder {
+        AStarBuilder::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{assert_optimal, Graph};
+    use crate::{AStar, GridMap, PathError, PathGenerator};
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_reopen_threshold() {
+        // 0 -> 1 is cheap but its heuristic is inflated (admissible, not consistent),
+        // so 3 is closed through the expensive 0 -> 2 branch before 1 is expanded
+        let graph = Graph {
+            edges: vec![
+                (0, 1, 1),
+                (0, 2, 4),
+                (1, 3, 1),
+                (2, 3, 1),
+                (3, 4, 3),
+                (4, 5, 1),
+            ],
+            heuristic: HashMap::from([(1, 5)]),
+        };
+        let target = (Some(5), Some(0));
+
+        let (path, stats) = AStar::builder().run_with_stats(&graph, (0, 0), target);
+        let path = path.unwrap();
+        assert_eq!(path, vec![(5, 0), (4, 0), (3, 0), (1, 0), (0, 0)]);
+        assert_optimal(&graph, (0, 0), (5, 0), &path);
+        assert_eq!(stats.reopened, 1);
+
+        let (path, stats) =
+            AStar::builder()
+                .reopen_threshold(4)
+                .run_with_stats(&graph, (0, 0), target);
+        assert_eq!(path.unwrap(), vec![(5, 0), (4, 0), (3, 0), (2, 0), (0, 0)]);
+        assert_eq!(stats.reopened, 0);
+    }
+
+    // counts calculate_cost calls
+    struct CountedCosts(GridMap, Cell<usize>);
+
+    impl PathGenerator for CountedCosts {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.0.generate_paths(from_position)
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            self.1.set(self.1.get() + 1);
+            self.0.calculate_cost(current_position, next_position)
+        }
+
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            self.0.calculate_heuristic_cost(position, target)
+        }
+    }
+
+    #[test]
+    fn test_allow_reopening() {
+        let map = CountedCosts(
+            GridMap::builder(8, 8)
+                .block_all([(3, 1), (3, 2), (3, 3), (3, 4), (3, 5)])
+                .build(),
+            Cell::new(0),
+        );
+        let target = (Some(7), Some(3));
+        let (path, stats) = AStar::builder().run_with_stats(&map, (0, 3), target);
+        let reopening_costs = map.1.replace(0);
+        let (trusted_path, trusted_stats) =
+            AStar::builder()
+                .allow_reopening(false)
+                .run_with_stats(&map, (0, 3), target);
+        assert_eq!(trusted_path, path);
+        assert_eq!(
+            (stats.reopened, trusted_stats.expanded),
+            (0, stats.expanded)
+        );
+        // edges back into closed cells are no longer costed
+        assert!(map.1.get() < reopening_costs);
+    }
+
+    #[test]
+    fn test_max_path_len() {
+        let map = GridMap::builder(5, 5)
+            .block_all([
+                (1, 0),
+                (1, 1),
+                (1, 2),
+                (1, 3),
+                (3, 1),
+                (3, 2),
+                (3, 3),
+                (3, 4),
+            ])
+            .build();
+        let target = (Some(4), Some(0));
+        let path = AStar::builder().try_run(&map, (0, 0), target).unwrap();
+        assert_eq!(path.len(), 13);
+        assert_eq!(
+            AStar::builder()
+                .max_path_len(4)
+                .try_run(&map, (0, 0), target),
+            Err(PathError::PathTooLong(4))
+        );
+        assert_eq!(
+            AStar::builder().max_path_len(12).run(&map, (0, 0), target),
+            None
+        );
+        assert_eq!(
+            AStar::builder().max_path_len(13).run(&map, (0, 0), target),
+            Some(path)
+        );
+    }
+
+    #[test]
+    fn test_zero_cost_cycle() {
+        // 0 <-> 1 <-> 2 <-> 0 cost nothing, leaving the cycle is the only real cost
+        let graph = Graph::new(vec![
+            (0, 1, 0),
+            (1, 0, 0),
+            (1, 2, 0),
+            (2, 1, 0),
+            (2, 0, 0),
+            (0, 2, 0),
+            (2, 3, 2),
+            (0, 3, 5),
+        ]);
+        let target = (Some(3), Some(0));
+        for builder in [AStar::builder(), AStar::builder().reopen_threshold(0)] {
+            let (path, stats) = builder.run_with_stats(&graph, (0, 0), target);
+            assert_eq!(path.unwrap(), vec![(3, 0), (2, 0), (0, 0)]);
+            assert_eq!(stats.reopened, 0);
+            assert_eq!(
+                builder.try_run(&graph, (0, 0), (Some(4), Some(0))),
+                Err(PathError::Unreachable)
+            );
+        }
+        assert_eq!(AStar::run(&graph, (1, 0), target).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_action_cost() {
+        // two rough steps over 1 or four smooth ones over 2, 3 and 5
+        let graph = Graph::new(vec![
+            (0, 1, 5),
+            (1, 4, 5),
+            (0, 2, 1),
+            (2, 3, 1),
+            (3, 5, 1),
+            (5, 4, 1),
+        ]);
+        let target = (Some(4), Some(0));
+        let smooth = vec![(4, 0), (5, 0), (3, 0), (2, 0), (0, 0)];
+        assert_eq!(
+            AStar::builder().run(&graph, (0, 0), target),
+            Some(smooth.clone())
+        );
+        assert_eq!(
+            AStar::builder().action_cost(2).run(&graph, (0, 0), target),
+            Some(smooth)
+        );
+        assert_eq!(
+            AStar::builder().action_cost(4).run(&graph, (0, 0), target),
+            Some(vec![(4, 0), (1, 0), (0, 0)])
+        );
+    }
+
+    #[test]
+    fn test_dense_closed_set_keeps_nodes_by_index() {
+        let map = GridMap::new(6, 6);
+        let target = (Some(5), Some(5));
+        let mut inst = AStar::builder().dense_closed_set(6, 6).instance(target);
+        let goal = inst.search(&map, (0, 0)).unwrap();
+        assert_eq!(
+            AStar::reconstruct_path(goal),
+            AStar::run(&map, (0, 0), target).unwrap()
+        );
+        assert!(inst.closed_nodes.is_empty());
+        assert_eq!(inst.closed_slots.iter().flatten().count(), inst.expanded);
+
+        // the cells outside of a smaller grid are closed as usual
+        let mut inst = AStar::builder().dense_closed_set(3, 3).instance(target);
+        let goal = inst.search(&map, (0, 0)).unwrap();
+        assert_eq!(AStar::reconstruct_path(goal).len(), 11);
+        assert!(inst
+            .closed_nodes
+            .iter()
+            .all(|node| node.position.0 >= 3 || node.position.1 >= 3));
+        assert_eq!(
+            inst.closed_slots.iter().flatten().count() + inst.closed_nodes.len(),
+            inst.expanded
+        );
+    }
+
+    #[test]
+    fn test_max_closed() {
+        let map = GridMap::builder(6, 6)
+            .block_all([
+                (1, 0),
+                (1, 1),
+                (1, 2),
+                (1, 3),
+                (3, 5),
+                (3, 4),
+                (3, 3),
+                (3, 2),
+            ])
+            .build();
+        let target = (Some(5), Some(0));
+        let (path, stats) = AStar::builder().run_with_stats(&map, (0, 0), target);
+        let (bounded_path, bounded_stats) =
+            AStar::builder()
+                .max_closed(2)
+                .run_with_stats(&map, (0, 0), target);
+        assert_eq!(bounded_path.unwrap().len(), path.unwrap().len());
+        assert!(bounded_stats.expanded >= stats.expanded);
+    }
+
+    thread_local! {
+        static CLOCK_START: Instant = Instant::now();
+        static CLOCK_OFFSET: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+    }
+
+    // only moves when SlowEdge says so
+    fn test_clock() -> Instant {
+        CLOCK_START.with(|start| *start) + CLOCK_OFFSET.with(Cell::get)
+    }
+
+    struct SlowEdge(GridMap);
+
+    impl PathGenerator for SlowEdge {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.0.generate_paths(from_position)
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            if current_position == (1, 0) && next_position == (2, 0) {
+                CLOCK_OFFSET.with(|offset| offset.set(offset.get() + Duration::from_millis(50)));
+            }
+            self.0.calculate_cost(current_position, next_position)
+        }
+
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            self.0.calculate_heuristic_cost(position, target)
+        }
+    }
+
+    #[test]
+    fn test_slow_edge_is_skipped() {
+        let map = SlowEdge(GridMap::new(4, 2));
+        let (path, stats) = AStar::builder()
+            .edge_time_budget(Duration::from_millis(20))
+            .clock(test_clock)
+            .run_with_stats(&map, (0, 0), (Some(3), Some(0)));
+        let path = path.unwrap();
+        assert_eq!(stats.slow_edges, 1);
+        assert_eq!(path.len(), 6);
+        assert!(!path.windows(2).any(|pair| pair == [(2, 0), (1, 0)]));
+
+        // a budget of 50ms still fits the edge
+        let (path, stats) = AStar::builder()
+            .edge_time_budget(Duration::from_millis(50))
+            .clock(test_clock)
+            .run_with_stats(&map, (0, 0), (Some(3), Some(0)));
+        assert_eq!(stats.slow_edges, 0);
+        assert_eq!(path.unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{AStar, Cost, PathGenerator};
+use std::cell::Cell;
+use std::collections::HashMap;
+
+// (start, goal, amount)
+pub type Demand = ((usize, usize), (usize, usize), usize);
+type Edge = ((usize, usize), (usize, usize));
+
+// every directed edge of inner carries at most capacity units, routed demands use it up
+pub struct CapacityMap<'a, T> {
+    inner: &'a T,
+    capacity: usize,
+    used: HashMap<Edge, usize>,
+    // amount of the demand that is being routed
+    amount: Cell<usize>,
+}
+
+impl<'a, T> CapacityMap<'a, T> {
+    pub fn new(inner: &'a T, capacity: usize) -> Self {
+        Self {
+            inner,
+            capacity,
+            used: HashMap::new(),
+            amount: Cell::new(0),
+        }
+    }
+
+    pub fn remaining(&self, from: (usize, usize), to: (usize, usize)) -> usize {
+        self.capacity - self.used.get(&(from, to)).copied().unwrap_or(0)
+    }
+
+    // each demand is routed over the edges the earlier ones left enough capacity on,
+    // so the order of demands decides who gets the short routes
+    pub fn route_sequential<C: Cost>(
+        &mut self,
+        demands: &[Demand],
+    ) -> Vec<Option<Vec<(usize, usize)>>>
+    where
+        T: PathGenerator<C>,
+    {
+        demands
+            .iter()
+            .map(|(start, goal, amount)| {
+                self.amount.set(*amount);
+                let path = AStar::run(&*self, *start, (Some(goal.0), Some(goal.1)))?;
+                for step in path.windows(2) {
+                    *self.used.entry((step[1], step[0])).or_insert(0) += amount;
+                }
+                Some(path)
+            })
+            .collect()
+    }
+}
+
+impl<C: Cost, T: PathGenerator<C>> PathGenerator<C> for CapacityMap<'_, T> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut possible_paths = self.inner.generate_paths(from_position);
+        possible_paths
+            .retain(|position| self.remaining(from_position, *position) >= self.amount.get());
+        possible_paths
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.inner.calculate_cost(current_position, next_position)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        self.inner.calculate_heuristic_cost(position, target)
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GridMap;
+
+    #[test]
+    fn test_saturated_edge_forces_detour() {
+        let map = GridMap::new(4, 2);
+        let mut capacity = CapacityMap::new(&map, 5);
+        let demands = [
+            ((0, 0), (3, 0), 4),
+            ((0, 0), (3, 0), 3),
+            ((0, 0), (3, 0), 6),
+        ];
+        let routes = capacity.route_sequential(&demands);
+        assert_eq!(routes[0], Some(vec![(3, 0), (2, 0), (1, 0), (0, 0)]));
+        let detour = routes[1].as_ref().unwrap();
+        assert_eq!(detour.len(), 6);
+        assert!(detour.contains(&(1, 1)));
+        assert_eq!(routes[2], None);
+        assert_eq!(capacity.remaining((0, 0), (1, 0)), 1);
+        assert_eq!(capacity.remaining((1, 0), (0, 0)), 5);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{AStar, PathGenerator};
+
+// extra cost of stepping next to an obstacle, halved every time the clearance doubles,
+// integer division makes every clearance above it free of penalty
+const CLEARANCE_PENALTY: usize = 8;
+
+struct ClearanceCost<'a, T, F> {
+    inner: &'a T,
+    clearance_of: F,
+}
+
+impl<T: PathGenerator, F: Fn((usize, usize)) -> usize> PathGenerator for ClearanceCost<'_, T, F> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner.generate_paths(from_position)
+    }
+
+    fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> usize {
+        let clearance = (self.clearance_of)(next_position).max(1);
+        self.inner.calculate_cost(current_position, next_position) + CLEARANCE_PENALTY / clearance
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> usize {
+        self.inner.calculate_heuristic_cost(position, target)
+    }
+}
+
+impl AStar {
+    // clearance_of is the distance from a cell to the nearest obstacle, steps pay 8 / clearance extra
+    // so the path is no longer the shortest one and clearances above 8 are all treated alike
+    pub fn run_max_clearance<T: PathGenerator>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        clearance_of: impl Fn((usize, usize)) -> usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        let wrapped = ClearanceCost {
+            inner: from_struct,
+            clearance_of,
+        };
+        AStar::run(&wrapped, start, target)
+    }
+}
+
+// narrowest spot along path, usize::MAX for an empty path
+pub fn path_min_clearance(
+    path: &[(usize, usize)],
+    clearance_of: impl Fn((usize, usize)) -> usize,
+) -> usize {
+    path.iter()
+        .map(|position| clearance_of(*position))
+        .min()
+        .unwrap_or(usize::MAX)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GridMap;
+
+    fn clearance_in(map: &GridMap) -> impl Fn((usize, usize)) -> usize + '_ {
+        |position: (usize, usize)| {
+            let mut clearance = usize::MAX;
+            for x in 0..map.width() {
+                for y in 0..map.height() {
+                    if map.is_blocked((x, y)) {
+                        clearance = clearance.min(x.abs_diff(position.0) + y.abs_diff(position.1));
+                    }
+                }
+            }
+            clearance
+        }
+    }
+
+    #[test]
+    fn test_wide_corridor_is_chosen() {
+        // a narrow corridor on row 1 and a 5 wide corridor on rows 3..=7
+        let mut map = GridMap::new(13, 9);
+        for x in 1..12 {
+            map.block((x, 0));
+            map.block((x, 2));
+            map.block((x, 8));
+        }
+        let clearance_of = clearance_in(&map);
+        let target = (Some(12), Some(1));
+
+        let shortest = AStar::run(&map, (0, 1), target).unwrap();
+        assert!(shortest.iter().all(|position| position.1 <= 1));
+
+        let widest = AStar::run_max_clearance(&map, (0, 1), target, clearance_of).unwrap();
+        assert!(widest.contains(&(6, 5)));
+        assert!(widest.len() > shortest.len());
+        assert_eq!(widest.first(), Some(&(12, 1)));
+    }
+
+    #[test]
+    fn test_path_min_clearance() {
+        // column 3 is open only at (3, 2)
+        let map = GridMap::builder(7, 5)
+            .block_all([(3, 0), (3, 1), (3, 3), (3, 4)])
+            .build();
+        let path = AStar::run(&map, (0, 2), (Some(6), Some(2))).unwrap();
+        assert!(path.contains(&(3, 2)));
+        assert_eq!(path_min_clearance(&path, clearance_in(&map)), 1);
+        assert_eq!(path_min_clearance(&path[..2], clearance_in(&map)), 3);
+        assert_eq!(path_min_clearance(&[], clearance_in(&map)), usize::MAX);
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+
+// membership of the expanded positions, the nodes themselves are kept by AStar
+pub trait ClosedSet {
+    fn insert(&mut self, position: (usize, usize));
+    fn remove(&mut self, position: (usize, usize));
+    fn contains(&self, position: (usize, usize)) -> bool;
+}
+
+impl ClosedSet for HashSet<(usize, usize)> {
+    fn insert(&mut self, position: (usize, usize)) {
+        HashSet::insert(self, position);
+    }
+
+    fn remove(&mut self, position: (usize, usize)) {
+        HashSet::remove(self, &position);
+    }
+
+    fn contains(&self, position: (usize, usize)) -> bool {
+        HashSet::contains(self, &position)
+    }
+}
+
+// one bit per cell of a width x height grid, every searched position has to lie inside it
+pub struct DenseClosedSet {
+    width: usize,
+    height: usize,
+    bits: Vec<u64>,
+}
+
+impl DenseClosedSet {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            bits: vec![0; (width * height).div_ceil(64)],
+        }
+    }
+
+    fn index(&self, position: (usize, usize)) -> Option<usize> {
+        if position.0 < self.width && position.1 < self.height {
+            return Some(position.1 * self.width + position.0);
+        }
+        None
+    }
+}
+
+impl ClosedSet for DenseClosedSet {
+    fn insert(&mut self, position: (usize, usize)) {
+        let index = self
+            .index(position)
+            .expect("position outside of the DenseClosedSet grid");
+        self.bits[index / 64] |= 1 << (index % 64);
+    }
+
+    fn remove(&mut self, position: (usize, usize)) {
+        if let Some(index) = self.index(position) {
+            self.bits[index / 64] &= !(1 << (index % 64));
+        }
+    }
+
+    fn contains(&self, position: (usize, usize)) -> bool {
+        self.index(position)
+            .is_some_and(|index| self.bits[index / 64] & (1 << (index % 64)) != 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_dense_closed_set() {
+        let mut closed = DenseClosedSet::new(10, 7);
+        closed.insert((9, 6));
+        closed.insert((3, 2));
+        assert!(closed.contains((9, 6)) && closed.contains((3, 2)));
+        assert!(!closed.contains((2, 3)) && !closed.contains((10, 0)));
+        closed.remove((9, 6));
+        assert!(!closed.contains((9, 6)));
+    }
+
+    #[test]
+    fn test_dense_matches_hash_set() {
+        let map = GridMap::builder(20, 20).diagonal(true).build();
+        let target = (Some(17), Some(19));
+        let (path, stats) = AStar::builder().run_with_stats(&map, (2, 0), target);
+        let (dense_path, dense_stats) =
+            AStar::builder()
+                .dense_closed_set(20, 20)
+                .run_with_stats(&map, (2, 0), target);
+        assert_eq!(dense_path, path);
+        assert_eq!(dense_stats.expanded, stats.expanded);
+    }
+}
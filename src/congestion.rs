@@ -0,0 +1,142 @@
+use crate::{AStar, Cost, PathGenerator};
+use std::collections::HashMap;
+
+// (start, goal)
+type Trip = ((usize, usize), (usize, usize));
+
+// entering a cell costs penalty extra for every route already laid through it
+pub struct CongestionMap<'a, T> {
+    inner: &'a T,
+    penalty: usize,
+    usage: HashMap<(usize, usize), usize>,
+}
+
+impl<'a, T: PathGenerator> CongestionMap<'a, T> {
+    pub fn new(inner: &'a T, penalty: usize) -> Self {
+        Self {
+            inner,
+            penalty,
+            usage: HashMap::new(),
+        }
+    }
+
+    // routes laid through position, start cells included
+    pub fn usage(&self, position: (usize, usize)) -> usize {
+        self.usage.get(&position).copied().unwrap_or(0)
+    }
+
+    // demands routed one after the other, each route counts for the later ones
+    pub fn route(&mut self, demands: &[Trip]) -> Vec<Option<Vec<(usize, usize)>>> {
+        demands
+            .iter()
+            .map(|(start, goal)| {
+                let path = AStar::run(&*self, *start, (Some(goal.0), Some(goal.1)))?;
+                for position in &path {
+                    *self.usage.entry(*position).or_insert(0) += 1;
+                }
+                Some(path)
+            })
+            .collect()
+    }
+}
+
+impl<T: PathGenerator> PathGenerator for CongestionMap<'_, T> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner.generate_paths(from_position)
+    }
+
+    fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> usize {
+        self.inner.calculate_cost(current_position, next_position)
+            + self.penalty * self.usage(next_position)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> usize {
+        self.inner.calculate_heuristic_cost(position, target)
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: usize) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+// how many of the shortest paths between pairs walk through each cell, start and goal
+// included, pairs without a path aren't counted
+pub fn betweenness<C: Cost, T: PathGenerator<C>>(
+    from_struct: &T,
+    pairs: &[Trip],
+) -> HashMap<(usize, usize), usize> {
+    let mut counts = HashMap::new();
+    for (start, goal) in pairs {
+        let path = AStar::run(from_struct, *start, (Some(goal.0), Some(goal.1)));
+        for position in path.into_iter().flatten() {
+            *counts.entry(position).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+impl AStar {
+    // CongestionMap::route on a fresh map
+    pub fn route_with_congestion<T: PathGenerator>(
+        from_struct: &T,
+        demands: &[Trip],
+        penalty: usize,
+    ) -> Vec<Option<Vec<(usize, usize)>>> {
+        CongestionMap::new(from_struct, penalty).route(demands)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GridMap;
+
+    #[test]
+    fn test_routes_spread_out() {
+        let map = GridMap::new(5, 3);
+        let demands = [((0, 1), (4, 1)); 3];
+        let mut congestion = CongestionMap::new(&map, 3);
+        let routes = congestion.route(&demands);
+        let routes: Vec<_> = routes.into_iter().map(Option::unwrap).collect();
+        assert!(routes[0].iter().all(|position| position.1 == 1));
+        assert!(routes[1].iter().any(|position| position.1 != 1));
+        assert!(routes[2].iter().any(|position| position.1 != 1));
+        assert_ne!(routes[1], routes[2]);
+        assert_eq!(congestion.usage((0, 1)), 3);
+        assert_eq!(congestion.usage((2, 1)), 1);
+
+        let free = AStar::route_with_congestion(&map, &demands, 0);
+        assert!(free.iter().all(|route| route.as_ref() == Some(&routes[0])));
+    }
+
+    #[test]
+    fn test_betweenness() {
+        // rooms on both sides of the corridor on row 2 from x = 3 to x = 5
+        let mut map = GridMap::new(9, 5);
+        map.extend((3..6).flat_map(|x| [(x, 0), (x, 1), (x, 3), (x, 4)]));
+        let pairs = [
+            ((0, 0), (8, 4)),
+            ((0, 4), (8, 0)),
+            ((1, 2), (7, 2)),
+            ((2, 1), (6, 3)),
+            ((0, 0), (1, 4)),
+            ((0, 0), (9, 9)),
+        ];
+        let counts = betweenness(&map, &pairs);
+        let highest = counts.values().copied().max().unwrap();
+        assert_eq!(highest, 4);
+        for x in 3..6 {
+            assert_eq!(counts[&(x, 2)], highest);
+        }
+        assert_eq!(counts[&(0, 0)], 2);
+        assert!(!counts.contains_key(&(9, 9)));
+    }
+}
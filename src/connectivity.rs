@@ -0,0 +1,110 @@
+use crate::{AStar, Cost, PathGenerator};
+use std::collections::HashSet;
+
+// every position reachable from start, from a single flood fill over generate_paths
+pub struct ConnectivityIndex {
+    start: (usize, usize),
+    reached: HashSet<(usize, usize)>,
+}
+
+impl ConnectivityIndex {
+    pub fn build<C: Cost, T: PathGenerator<C>>(from_struct: &T, start: (usize, usize)) -> Self {
+        let mut reached = HashSet::from([start]);
+        let mut frontier = vec![start];
+        while let Some(position) = frontier.pop() {
+            for possible_path in from_struct.generate_paths(position) {
+                if reached.insert(possible_path) {
+                    frontier.push(possible_path);
+                }
+            }
+        }
+        Self { start, reached }
+    }
+
+    pub fn start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    // on directed maps this is only reachability from start, not the way back
+    pub fn connected(&self, position: (usize, usize)) -> bool {
+        self.reached.contains(&position)
+    }
+}
+
+// cells other than start and goal that every path from start to goal walks through, so
+// blocking any one of them disconnects goal, ordered like the path of run
+pub fn critical_cells<C: Cost, T: PathGenerator<C>>(
+    from_struct: &T,
+    start: (usize, usize),
+    goal: (usize, usize),
+) -> Vec<(usize, usize)> {
+    // every cut cell lies on any path, so only the cells of one need testing
+    let Some(path) = AStar::run(from_struct, start, (Some(goal.0), Some(goal.1))) else {
+        return Vec::new();
+    };
+    path.iter()
+        .filter(|cell| **cell != start && **cell != goal)
+        .filter(|cell| !reaches_without(from_struct, start, goal, **cell))
+        .copied()
+        .collect()
+}
+
+fn reaches_without<C: Cost, T: PathGenerator<C>>(
+    from_struct: &T,
+    start: (usize, usize),
+    goal: (usize, usize),
+    removed: (usize, usize),
+) -> bool {
+    let mut reached = HashSet::from([start, removed]);
+    let mut frontier = vec![start];
+    while let Some(position) = frontier.pop() {
+        if position == goal {
+            return true;
+        }
+        for possible_path in from_struct.generate_paths(position) {
+            if reached.insert(possible_path) {
+                frontier.push(possible_path);
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GridMap;
+
+    #[test]
+    fn test_connected() {
+        // column 2 splits the map in two
+        let map = GridMap::builder(5, 3)
+            .block_all([(2, 0), (2, 1), (2, 2)])
+            .build();
+        let index = ConnectivityIndex::build(&map, (0, 1));
+        assert!(index.connected((0, 1)) && index.connected((1, 2)));
+        assert!(!index.connected((3, 0)) && !index.connected((2, 1)));
+        assert!(!index.connected((7, 7)));
+        let connected = (0..5).flat_map(|x| (0..3).map(move |y| (x, y)));
+        assert_eq!(connected.filter(|cell| index.connected(*cell)).count(), 6);
+    }
+
+    #[test]
+    fn test_critical_cells() {
+        // rooms joined by the doors at (3, 2) and (6, 4), a door and the cells on both sides of
+        // it are the only way through
+        let mut map = GridMap::new(9, 5);
+        map.extend((0..5).filter(|y| *y != 2).map(|y| (3, y)));
+        map.extend((0..5).filter(|y| *y != 4).map(|y| (6, y)));
+        assert_eq!(
+            critical_cells(&map, (0, 0), (8, 0)),
+            vec![(7, 4), (6, 4), (5, 4), (4, 2), (3, 2), (2, 2)]
+        );
+        assert_eq!(
+            critical_cells(&map, (0, 0), (5, 0)),
+            vec![(4, 2), (3, 2), (2, 2)]
+        );
+        assert!(critical_cells(&map, (0, 0), (2, 4)).is_empty());
+        assert!(critical_cells(&map, (0, 0), (3, 0)).is_empty());
+    }
+}
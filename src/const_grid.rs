@@ -0,0 +1,97 @@
+use crate::PathGenerator;
+
+// 4-connected W x H grid whose blocked cells are one bit each in a row of an array. The map
+// itself never touches the heap, but generate_paths and every search allocate, so it doesn't
+// make the crate usable without an allocator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstGrid<const W: usize, const H: usize> {
+    blocked: [u128; H],
+}
+
+impl<const W: usize, const H: usize> ConstGrid<W, H> {
+    pub const fn new() -> Self {
+        const { assert!(W <= 128, "a ConstGrid row holds at most 128 cells") };
+        Self { blocked: [0; H] }
+    }
+
+    pub const fn contains(&self, position: (usize, usize)) -> bool {
+        position.0 < W && position.1 < H
+    }
+
+    pub const fn is_blocked(&self, position: (usize, usize)) -> bool {
+        self.contains(position) && self.blocked[position.1] & (1 << position.0) != 0
+    }
+
+    pub fn block(&mut self, position: (usize, usize)) {
+        assert!(self.contains(position), "position outside of the ConstGrid");
+        self.blocked[position.1] |= 1 << position.0;
+    }
+
+    pub fn unblock(&mut self, position: (usize, usize)) {
+        assert!(self.contains(position), "position outside of the ConstGrid");
+        self.blocked[position.1] &= !(1 << position.0);
+    }
+}
+
+impl<const W: usize, const H: usize> Default for ConstGrid<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> PathGenerator for ConstGrid<W, H> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let (x, y) = from_position;
+        [
+            x.checked_sub(1).map(|x| (x, y)),
+            y.checked_sub(1).map(|y| (x, y)),
+            Some((x + 1, y)),
+            Some((x, y + 1)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|position| self.contains(*position) && !self.is_blocked(*position))
+        .collect()
+    }
+
+    #[allow(unused_variables)]
+    fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> usize {
+        1
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> usize {
+        target.0.map_or(0, |x| x.abs_diff(position.0))
+            + target.1.map_or(0, |y| y.abs_diff(position.1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::AStar;
+
+    #[test]
+    fn test_const_grid() {
+        let mut grid = ConstGrid::<8, 8>::new();
+        for y in 0..7 {
+            grid.block((3, y));
+        }
+        grid.unblock((3, 0));
+        grid.block((3, 0));
+        assert!(grid.is_blocked((3, 6)) && !grid.is_blocked((3, 7)));
+        assert!(!grid.is_blocked((9, 0)));
+
+        let path = AStar::run(&grid, (0, 0), (Some(7), Some(0))).unwrap();
+        assert_eq!(path.len(), 22);
+        assert!(path.contains(&(3, 7)));
+        assert!(path.iter().all(|position| !grid.is_blocked(*position)));
+    }
+}
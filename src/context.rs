@@ -0,0 +1,89 @@
+use crate::{AStar, GridMap, PathGenerator};
+use std::collections::HashMap;
+
+// keeps the cost fields of earlier queries on a GridMap that changes now and then,
+// obstacle changes only drop the fields they can affect
+pub struct SearchContext {
+    map: GridMap,
+    fields: HashMap<(usize, usize), HashMap<(usize, usize), usize>>,
+    // cost fields computed so far, reused fields don't count
+    sweeps: usize,
+}
+
+impl SearchContext {
+    pub fn new(map: GridMap) -> Self {
+        Self {
+            map,
+            fields: HashMap::new(),
+            sweeps: 0,
+        }
+    }
+
+    pub fn map(&self) -> &GridMap {
+        &self.map
+    }
+
+    pub fn sweeps(&self) -> usize {
+        self.sweeps
+    }
+
+    pub fn cost(&mut self, start: (usize, usize), goal: (usize, usize)) -> Option<usize> {
+        if !self.fields.contains_key(&start) {
+            self.sweeps += 1;
+            let field = AStar::cost_field(&self.map, start);
+            self.fields.insert(start, field);
+        }
+        self.fields[&start].get(&goal).copied()
+    }
+
+    // a cell no field reached can't change any cost by being blocked
+    pub fn add_obstacle(&mut self, position: (usize, usize)) {
+        self.map.block(position);
+        self.fields
+            .retain(|_, field| !field.contains_key(&position));
+    }
+
+    // an opened cell can only shorten fields that reached one of its neighbours
+    pub fn remove_obstacle(&mut self, position: (usize, usize)) {
+        self.map.unblock(position);
+        let neighbours = self.map.generate_paths(position);
+        self.fields.retain(|_, field| {
+            !neighbours
+                .iter()
+                .any(|neighbour| field.contains_key(neighbour))
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_invalidates_only_affected_fields() {
+        // column 3 splits the map into two rooms
+        let map = GridMap::builder(7, 3)
+            .block_all([(3, 0), (3, 1), (3, 2)])
+            .build();
+        let mut context = SearchContext::new(map);
+        assert_eq!(context.cost((0, 0), (2, 2)), Some(4));
+        assert_eq!(context.cost((6, 0), (4, 2)), Some(4));
+        assert_eq!(context.cost((0, 0), (2, 0)), Some(2));
+        assert_eq!(context.sweeps(), 2);
+
+        context.add_obstacle((1, 0));
+        assert_eq!(context.cost((0, 0), (2, 0)), Some(4));
+        assert_eq!(context.cost((6, 0), (4, 2)), Some(4));
+        assert_eq!(context.sweeps(), 3);
+
+        context.remove_obstacle((3, 1));
+        assert_eq!(context.cost((0, 0), (6, 0)), Some(8));
+        assert_eq!(context.cost((6, 0), (0, 0)), Some(8));
+        assert_eq!(context.sweeps(), 5);
+        assert_eq!(
+            context.cost((0, 0), (6, 0)),
+            AStar::shortest_cost(context.map(), (0, 0), (6, 0))
+        );
+        assert_eq!(context.sweeps(), 5);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::{AStar, Cost, PathGenerator};
+use std::collections::HashSet;
+
+// only steps onto allowed cells, start is never generated so it doesn't need to be allowed
+struct Corridor<'a, T> {
+    inner: &'a T,
+    allowed: HashSet<(usize, usize)>,
+}
+
+impl<C: Cost, T: PathGenerator<C>> PathGenerator<C> for Corridor<'_, T> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut possible_paths = self.inner.generate_paths(from_position);
+        possible_paths.retain(|position| self.allowed.contains(position));
+        possible_paths
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.inner.calculate_cost(current_position, next_position)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        self.inner.calculate_heuristic_cost(position, target)
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+impl<C: Cost> AStar<C> {
+    // keeps the path within width(progress) cells (Chebyshev) of reference, progress goes from
+    // 0.0 at the start to 1.0 at the target. reference is ordered like the paths of run, from
+    // the target back to the start, and width is called once for every cell of it
+    pub fn run_in_corridor<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        reference: &[(usize, usize)],
+        mut width: impl FnMut(f64) -> usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        let last = reference.len().saturating_sub(1).max(1) as f64;
+        let mut allowed = HashSet::new();
+        for (index, cell) in reference.iter().rev().enumerate() {
+            let width = width(index as f64 / last);
+            for x in cell.0.saturating_sub(width)..=cell.0.saturating_add(width) {
+                for y in cell.1.saturating_sub(width)..=cell.1.saturating_add(width) {
+                    allowed.insert((x, y));
+                }
+            }
+        }
+        let corridor = Corridor {
+            inner: from_struct,
+            allowed,
+        };
+        AStar::run(&corridor, start, target)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_narrowing_corridor() {
+        // the wall on column 7 needs a 2 cell deviation from the straight reference
+        let map = GridMap::builder(15, 7)
+            .block_all([(7, 2), (7, 3), (7, 4)])
+            .build();
+        let reference: Vec<(usize, usize)> = (0..15).rev().map(|x| (x, 3)).collect();
+        let target = (Some(14), Some(3));
+        let narrowing = |progress: f64| ((1.0 - progress) * 3.0).round() as usize;
+
+        let path = AStar::run_in_corridor(&map, (0, 3), target, &reference, narrowing).unwrap();
+        assert_eq!(path.first(), Some(&(14, 3)));
+        let in_corridor = |position: &(usize, usize)| {
+            (0..15).any(|x: usize| {
+                let distance = position.0.abs_diff(x).max(position.1.abs_diff(3));
+                distance <= narrowing(x as f64 / 14.0)
+            })
+        };
+        assert!(path.iter().all(in_corridor));
+        assert!(path.iter().any(|position| position.1.abs_diff(3) == 2));
+        assert_eq!(
+            AStar::run_in_corridor(&map, (0, 3), target, &reference, |_| 1),
+            None
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use std::ops::Add;
+
+// zero is taken from Default
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {
+    // an edge costing exactly this is skipped as if generate_paths hadn't returned it,
+    // None for cost types without such a value
+    const INFINITY: Option<Self> = None;
+
+    // the one check for INFINITY every search loop goes through
+    fn is_infinite(self) -> bool {
+        Self::INFINITY == Some(self)
+    }
+}
+
+macro_rules! impl_cost {
+    ($($cost:ty),*) => {
+        $(impl Cost for $cost {
+            const INFINITY: Option<Self> = Some(<$cost>::MAX);
+        })*
+    };
+}
+
+impl_cost!(u8, u16, u32, u64, u128, usize);
+
+// fixed point cost in thousandths, gives fractional diagonal moves without floats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct MilliCost(pub u64);
+
+impl MilliCost {
+    pub const ORTHOGONAL: Self = Self(1000);
+    pub const DIAGONAL: Self = Self(1414);
+}
+
+impl Add for MilliCost {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl Cost for MilliCost {
+    const INFINITY: Option<Self> = Some(Self(u64::MAX));
+}
+
+// minimizes the first cost and breaks ties by the second one, e.g. time and then toll.
+// Ord compares the fields in order, addition is per field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct LexCost<A, B>(pub A, pub B);
+
+impl<A: Cost, B: Cost> Add for LexCost<A, B> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0, self.1 + other.1)
+    }
+}
+
+impl<A: Cost, B: Cost> Cost for LexCost<A, B> {
+    const INFINITY: Option<Self> = match (A::INFINITY, B::INFINITY) {
+        (Some(first), Some(second)) => Some(Self(first, second)),
+        _ => None,
+    };
+}
+
+// in whole units
+impl From<MilliCost> for f64 {
+    fn from(cost: MilliCost) -> f64 {
+        cost.0 as f64 / 1000.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap, PathGenerator};
+
+    struct OctileMap(GridMap);
+
+    impl PathGenerator<MilliCost> for OctileMap {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.0.generate_paths(from_position)
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> MilliCost {
+            if current_position.0 != next_position.0 && current_position.1 != next_position.1 {
+                return MilliCost::DIAGONAL;
+            }
+            MilliCost::ORTHOGONAL
+        }
+
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> MilliCost {
+            let dx = target.0.map_or(0, |x| x.abs_diff(position.0)) as u64;
+            let dy = target.1.map_or(0, |y| y.abs_diff(position.1)) as u64;
+            let diagonal = dx.min(dy);
+            MilliCost(diagonal * MilliCost::DIAGONAL.0 + (dx.max(dy) - diagonal) * 1000)
+        }
+    }
+
+    // time is 1 a step, entering a cell of row 0 costs a toll of 3
+    struct TollMap(GridMap);
+
+    impl PathGenerator<LexCost<usize, usize>> for TollMap {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.0.generate_paths(from_position)
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> LexCost<usize, usize> {
+            let toll = if next_position.1 == 0 { 3 } else { 0 };
+            LexCost(self.0.calculate_cost(current_position, next_position), toll)
+        }
+
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> LexCost<usize, usize> {
+            LexCost(self.0.calculate_heuristic_cost(position, target), 0)
+        }
+    }
+
+    #[test]
+    fn test_lex_cost_breaks_ties_by_toll() {
+        let map = TollMap(GridMap::new(4, 2));
+        // every 4 step route takes the same time, only leaving row 0 right away is toll free
+        let result = AStar::run_result(&map, (0, 0), (Some(3), Some(1))).unwrap();
+        assert_eq!(result.cost, LexCost(4, 0));
+        assert_eq!(result.path, vec![(3, 1), (2, 1), (1, 1), (0, 1), (0, 0)]);
+        // time comes first, the tolls of row 0 are paid to save 2 steps
+        let result = AStar::run_result(&map, (0, 0), (Some(3), Some(0))).unwrap();
+        assert_eq!(result.cost, LexCost(3, 9));
+        assert!(LexCost(3, 9) < LexCost(4, 0) && LexCost(4, 0) < LexCost(4, 1));
+        assert_eq!(
+            LexCost::<usize, usize>::INFINITY,
+            Some(LexCost(usize::MAX, usize::MAX))
+        );
+    }
+
+    #[test]
+    fn test_milli_cost_diagonal_path() {
+        let map = OctileMap(GridMap::builder(5, 5).diagonal(true).build());
+        let steps = AStar::run_with_step_costs(&map, (0, 0), (Some(3), Some(2))).unwrap();
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps.last(), Some(&((3, 2), MilliCost(2 * 1414 + 1000))));
+    }
+
+    // blocked cells of the map are entered for usize::MAX instead of being filtered out
+    struct InfiniteWalls(GridMap);
+
+    impl PathGenerator for InfiniteWalls {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            let (x, y) = from_position;
+            [
+                (x.wrapping_sub(1), y),
+                (x, y.wrapping_sub(1)),
+                (x + 1, y),
+                (x, y + 1),
+            ]
+            .into_iter()
+            .filter(|position| self.0.contains(*position))
+            .collect()
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            if self.0.is_blocked(next_position) {
+                return usize::MAX;
+            }
+            self.0.calculate_cost(current_position, next_position)
+        }
+
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            self.0.calculate_heuristic_cost(position, target)
+        }
+    }
+
+    #[test]
+    fn test_infinite_cost_is_impassable() {
+        let map = GridMap::builder(5, 4)
+            .block_all([(2, 0), (2, 1), (2, 2)])
+            .build();
+        let target = (Some(4), Some(0));
+        let walls = InfiniteWalls(
+            GridMap::builder(5, 4)
+                .block_all([(2, 0), (2, 1), (2, 2)])
+                .build(),
+        );
+        let path = AStar::run(&walls, (0, 0), target).unwrap();
+        assert_eq!(
+            Some(path.len()),
+            AStar::run(&map, (0, 0), target).map(|p| p.len())
+        );
+        assert!(path.iter().all(|position| map.is_passable(*position)));
+        assert_eq!(
+            AStar::cost_field(&walls, (0, 0)),
+            AStar::cost_field(&map, (0, 0))
+        );
+
+        let walls = InfiniteWalls(
+            GridMap::builder(5, 4)
+                .block_all((0..4).map(|y| (2, y)))
+                .build(),
+        );
+        assert_eq!(AStar::run(&walls, (0, 0), target), None);
+        assert_eq!(usize::INFINITY, Some(usize::MAX));
+    }
+}
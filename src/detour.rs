@@ -0,0 +1,76 @@
+use crate::{AStar, Cost, PathGenerator};
+
+// inner without the blocked cell
+struct WithBlocked<'a, T> {
+    inner: &'a T,
+    blocked: (usize, usize),
+}
+
+impl<C: Cost, T: PathGenerator<C>> PathGenerator<C> for WithBlocked<'_, T> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut possible_paths = self.inner.generate_paths(from_position);
+        possible_paths.retain(|position| *position != self.blocked);
+        possible_paths
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.inner.calculate_cost(current_position, next_position)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        self.inner.calculate_heuristic_cost(position, target)
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+impl<C: Cost> AStar<C> {
+    // optimal cost before and after newly_blocked is blocked, None when the target can't be
+    // reached afterwards. Blocking start itself only keeps the search from coming back to it
+    pub fn detour_cost<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        newly_blocked: (usize, usize),
+    ) -> Option<(C, C)> {
+        let original = Self::run_result(from_struct, start, target)?;
+        if !original.path.contains(&newly_blocked) {
+            return Some((original.cost, original.cost));
+        }
+        let blocked = WithBlocked {
+            inner: from_struct,
+            blocked: newly_blocked,
+        };
+        let detour = Self::run_result(&blocked, start, target)?;
+        Some((original.cost, detour.cost))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_detour_cost() {
+        let map = GridMap::builder(5, 3).block_all([(2, 0), (2, 1)]).build();
+        let target = (Some(4), Some(0));
+        assert_eq!(AStar::detour_cost(&map, (0, 0), target, (2, 2)), None);
+
+        let map = GridMap::builder(5, 4).block_all([(2, 0), (2, 1)]).build();
+        assert_eq!(
+            AStar::detour_cost(&map, (0, 0), target, (2, 2)),
+            Some((8, 10))
+        );
+        // off the optimal path nothing changes
+        assert_eq!(
+            AStar::detour_cost(&map, (0, 0), target, (0, 3)),
+            Some((8, 8))
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{Cost, Direction, PathGenerator};
+use std::collections::HashMap;
+
+// cells that can only be entered from some sides, a side is the offset from the cell to the
+// neighbour it's entered from, e.g. (0, 1) for the cell below it. Offsets are taken between
+// raw positions, so steps around a wrapping map don't match
+pub struct DirectionalMap<G> {
+    inner: G,
+    entries: HashMap<(usize, usize), Vec<Direction>>,
+}
+
+impl<G> DirectionalMap<G> {
+    pub fn new(inner: G) -> Self {
+        Self {
+            inner,
+            entries: HashMap::new(),
+        }
+    }
+
+    // once one side is allowed every other side of position is forbidden
+    pub fn allow_entry(mut self, position: (usize, usize), from: Direction) -> Self {
+        self.entries.entry(position).or_default().push(from);
+        self
+    }
+
+    fn can_enter(&self, from_position: (usize, usize), position: (usize, usize)) -> bool {
+        match self.entries.get(&position) {
+            Some(sides) => sides.contains(&(
+                from_position.0 as i64 - position.0 as i64,
+                from_position.1 as i64 - position.1 as i64,
+            )),
+            None => true,
+        }
+    }
+}
+
+impl<G: PathGenerator<C>, C: Cost> PathGenerator<C> for DirectionalMap<G> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut possible_paths = self.inner.generate_paths(from_position);
+        possible_paths.retain(|position| self.can_enter(from_position, *position));
+        possible_paths
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.inner.calculate_cost(current_position, next_position)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        self.inner.calculate_heuristic_cost(position, target)
+    }
+
+    fn reverse_neighbors(&self, to_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut possible_paths = self.inner.reverse_neighbors(to_position);
+        possible_paths.retain(|position| self.can_enter(*position, to_position));
+        possible_paths
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_ramp_entered_from_south() {
+        // the ramp at (2, 1) is the only way through the wall on column 2
+        let map = GridMap::builder(5, 3).block_all([(2, 0), (2, 2)]).build();
+        let ramp = DirectionalMap::new(map).allow_entry((2, 1), (0, 1));
+        assert_eq!(ramp.generate_paths((1, 1)), vec![(0, 1), (1, 0), (1, 2)]);
+        assert_eq!(AStar::run(&ramp, (0, 1), (Some(4), Some(1))), None);
+
+        let map = GridMap::builder(5, 3).block_all([(2, 0)]).build();
+        let ramp = DirectionalMap::new(map).allow_entry((2, 1), (0, 1));
+        let path = AStar::run(&ramp, (3, 0), (Some(2), Some(1))).unwrap();
+        assert_eq!(path, vec![(2, 1), (2, 2), (3, 2), (3, 1), (3, 0)]);
+        assert_eq!(ramp.reverse_neighbors((2, 1)), vec![(2, 2)]);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{GridMap, PathGenerator};
+
+// GridMap whose steps cost 1 plus uphill_penalty for every unit climbed, going down is free,
+// so the heuristic of the map stays admissible
+pub struct ElevationMap {
+    map: GridMap,
+    // row by row, map.width() values per row
+    heights: Vec<usize>,
+    uphill_penalty: usize,
+}
+
+impl ElevationMap {
+    pub fn new(map: GridMap, heights: Vec<usize>, uphill_penalty: usize) -> Self {
+        assert_eq!(
+            heights.len(),
+            map.width() * map.height(),
+            "an ElevationMap needs one height for every cell"
+        );
+        Self {
+            map,
+            heights,
+            uphill_penalty,
+        }
+    }
+
+    pub fn height_at(&self, position: (usize, usize)) -> usize {
+        self.heights[position.1 * self.map.width() + position.0]
+    }
+}
+
+impl PathGenerator for ElevationMap {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.map.generate_paths(from_position)
+    }
+
+    fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> usize {
+        let climb = self
+            .height_at(next_position)
+            .saturating_sub(self.height_at(current_position));
+        self.map.calculate_cost(current_position, next_position) + climb * self.uphill_penalty
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> usize {
+        self.map.calculate_heuristic_cost(position, target)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::AStar;
+
+    #[test]
+    fn test_flat_detour_beats_steep_climb() {
+        // a ridge of height 3 on row 0, row 1 and 2 are flat
+        #[rustfmt::skip]
+        let heights = vec![
+            0, 3, 3, 3, 0,
+            0, 0, 0, 0, 0,
+            0, 0, 0, 0, 0,
+        ];
+        let target = (Some(4), Some(0));
+        let flat = ElevationMap::new(GridMap::new(5, 3), heights.clone(), 0);
+        assert_eq!(AStar::run(&flat, (0, 0), target).unwrap().len(), 5);
+
+        let steep = ElevationMap::new(GridMap::new(5, 3), heights, 1);
+        let path = AStar::run_result(&steep, (0, 0), target).unwrap();
+        assert!(path
+            .path
+            .iter()
+            .all(|position| steep.height_at(*position) == 0));
+        assert_eq!((path.path.len(), path.cost), (7, 6));
+    }
+}
@@ -0,0 +1,109 @@
+use crate::PathError;
+
+// 8 directions, a step is stored as its index in 3 bits
+const DIRECTIONS: [(i64, i64); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+// the number of steps as 4 little endian bytes, then the steps packed 3 bits each,
+// the first cell isn't stored and has to be given to decode_grid_path
+pub fn encode_grid_path(path: &[(usize, usize)]) -> Result<Vec<u8>, PathError> {
+    let steps = path.len().saturating_sub(1);
+    let count = u32::try_from(steps).map_err(|_| PathError::TooManySteps(steps))?;
+    let mut bytes = count.to_le_bytes().to_vec();
+    bytes.resize(4 + (steps * 3).div_ceil(8), 0);
+    for (index, pair) in path.windows(2).enumerate() {
+        let step = (
+            pair[1].0 as i64 - pair[0].0 as i64,
+            pair[1].1 as i64 - pair[0].1 as i64,
+        );
+        let code = DIRECTIONS
+            .iter()
+            .position(|direction| *direction == step)
+            .ok_or(PathError::NonAdjacentStep(pair[0], pair[1]))?;
+        for bit in 0..3 {
+            if code & (1 << bit) != 0 {
+                let position = index * 3 + bit;
+                bytes[4 + position / 8] |= 1 << (position % 8);
+            }
+        }
+    }
+    Ok(bytes)
+}
+
+pub fn decode_grid_path(
+    bytes: &[u8],
+    start: (usize, usize),
+) -> Result<Vec<(usize, usize)>, PathError> {
+    let header: [u8; 4] = bytes
+        .get(..4)
+        .and_then(|header| header.try_into().ok())
+        .ok_or(PathError::InvalidEncoding)?;
+    let steps = u32::from_le_bytes(header) as usize;
+    if bytes.len() != 4 + (steps * 3).div_ceil(8) {
+        return Err(PathError::InvalidEncoding);
+    }
+    let mut path = vec![start];
+    for index in 0..steps {
+        let code = (0..3).fold(0, |code, bit| {
+            let position = index * 3 + bit;
+            code | (((bytes[4 + position / 8] >> (position % 8)) & 1) as usize) << bit
+        });
+        let (x, y) = path[path.len() - 1];
+        let next = (
+            x.checked_add_signed(DIRECTIONS[code].0 as isize),
+            y.checked_add_signed(DIRECTIONS[code].1 as isize),
+        );
+        match next {
+            (Some(x), Some(y)) => path.push((x, y)),
+            _ => return Err(PathError::InvalidEncoding),
+        }
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_round_trip() {
+        let map = GridMap::builder(9, 9)
+            .diagonal(true)
+            .block_all([(4, 2), (4, 3), (4, 4), (4, 5)])
+            .build();
+        let path = AStar::run(&map, (8, 8), (Some(0), Some(1))).unwrap();
+        let bytes = encode_grid_path(&path).unwrap();
+        assert_eq!(bytes.len(), 4 + ((path.len() - 1) * 3).div_ceil(8));
+        assert_eq!(decode_grid_path(&bytes, path[0]), Ok(path));
+        assert_eq!(
+            decode_grid_path(&encode_grid_path(&[(3, 3)]).unwrap(), (3, 3)),
+            Ok(vec![(3, 3)])
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            encode_grid_path(&[(0, 0), (1, 1), (3, 1)]),
+            Err(PathError::NonAdjacentStep((1, 1), (3, 1)))
+        );
+        let bytes = encode_grid_path(&[(1, 0), (0, 0)]).unwrap();
+        assert_eq!(
+            decode_grid_path(&bytes, (0, 0)),
+            Err(PathError::InvalidEncoding)
+        );
+        assert_eq!(
+            decode_grid_path(&bytes[..4], (1, 0)),
+            Err(PathError::InvalidEncoding)
+        );
+    }
+}
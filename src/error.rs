@@ -0,0 +1,126 @@
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+// why a search ended without a path, returned by AStarBuilder::try_run,
+// or why a path couldn't be encoded or decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathError {
+    // every reachable node was expanded without reaching the target
+    Unreachable,
+    // the search ran for longer than the timeout
+    Timeout(Duration),
+    // the cancel flag was set while searching
+    Cancelled,
+    // two consecutive cells of a path that aren't neighbours on an 8 connected grid
+    NonAdjacentStep((usize, usize), (usize, usize)),
+    // the bytes weren't made by encode_grid_path or lead off the grid
+    InvalidEncoding,
+    // a path of more steps than the 4 byte count of encode_grid_path holds
+    TooManySteps(usize),
+    // the path found has more cells than the max_path_len of the builder
+    PathTooLong(usize),
+    // the shared budget of run_with_budget_counter ran out before the target was reached
+    BudgetExhausted,
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unreachable => f.write_str("the target can't be reached from the start"),
+            Self::Timeout(timeout) => write!(f, "no path was found within {timeout:?}"),
+            Self::Cancelled => f.write_str("the search was cancelled"),
+            Self::NonAdjacentStep(from, to) => {
+                write!(
+                    f,
+                    "the step from {from:?} to {to:?} isn't to a neighbouring cell"
+                )
+            }
+            Self::InvalidEncoding => f.write_str("the bytes aren't a valid encoded path"),
+            Self::TooManySteps(steps) => {
+                write!(f, "a path of {steps} steps is too long to be encoded")
+            }
+            Self::PathTooLong(max_path_len) => {
+                write!(f, "the path is longer than {max_path_len} cells")
+            }
+            Self::BudgetExhausted => f.write_str("the expansion budget ran out"),
+        }
+    }
+}
+
+// none of the variants wraps another error, so source stays None
+impl Error for PathError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap};
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            PathError::Unreachable.to_string(),
+            "the target can't be reached from the start"
+        );
+        assert_eq!(
+            PathError::Timeout(Duration::from_millis(20)).to_string(),
+            "no path was found within 20ms"
+        );
+        assert_eq!(PathError::Cancelled.to_string(), "the search was cancelled");
+        assert_eq!(
+            PathError::NonAdjacentStep((0, 0), (2, 0)).to_string(),
+            "the step from (0, 0) to (2, 0) isn't to a neighbouring cell"
+        );
+        assert_eq!(
+            PathError::InvalidEncoding.to_string(),
+            "the bytes aren't a valid encoded path"
+        );
+        assert_eq!(
+            PathError::TooManySteps(7).to_string(),
+            "a path of 7 steps is too long to be encoded"
+        );
+        assert_eq!(
+            PathError::PathTooLong(4).to_string(),
+            "the path is longer than 4 cells"
+        );
+        assert_eq!(
+            PathError::BudgetExhausted.to_string(),
+            "the expansion budget ran out"
+        );
+
+        let boxed: Box<dyn Error + Send + Sync> = Box::new(PathError::Cancelled);
+        assert!(boxed.source().is_none());
+        assert_eq!(boxed.downcast_ref(), Some(&PathError::Cancelled));
+    }
+
+    #[test]
+    fn test_try_run() {
+        let map = GridMap::builder(3, 3)
+            .block_all([(1, 0), (1, 1), (1, 2)])
+            .build();
+        let target = (Some(2), Some(0));
+        assert_eq!(
+            AStar::builder().try_run(&map, (0, 0), target),
+            Err(PathError::Unreachable)
+        );
+        assert_eq!(
+            AStar::builder().try_run(&map, (0, 0), (Some(0), Some(2))),
+            Ok(vec![(0, 2), (0, 1), (0, 0)])
+        );
+        assert_eq!(
+            AStar::builder()
+                .timeout(Duration::ZERO)
+                .try_run(&map, (0, 0), target),
+            Err(PathError::Timeout(Duration::ZERO))
+        );
+        let cancel = Arc::new(AtomicBool::new(true));
+        assert_eq!(
+            AStar::builder()
+                .cancel_flag(cancel)
+                .try_run(&map, (0, 0), target),
+            Err(PathError::Cancelled)
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{GridMap, PathGenerator};
+
+// GridMap over a flow field, a step costs neutral_cost - flow . direction times the step cost
+// of the map with the flow of the cell it leaves, so going with the flow is cheaper and
+// against it dearer. A step never costs less than 1, the heuristic of the map is scaled by the
+// cheapest step any flow allows to stay admissible
+pub struct FlowField {
+    map: GridMap,
+    // row by row, map.width() vectors per row
+    flows: Vec<(i64, i64)>,
+    neutral_cost: usize,
+    cheapest_step: usize,
+}
+
+impl FlowField {
+    pub fn new(map: GridMap, flows: Vec<(i64, i64)>, neutral_cost: usize) -> Self {
+        assert_eq!(
+            flows.len(),
+            map.width() * map.height(),
+            "a FlowField needs one flow for every cell"
+        );
+        // a unit or diagonal step gains at most |x| + |y| from a flow
+        let strongest = flows
+            .iter()
+            .map(|flow| flow.0.unsigned_abs() + flow.1.unsigned_abs())
+            .max()
+            .unwrap_or(0);
+        let cheapest_step = (neutral_cost as u64).saturating_sub(strongest).max(1) as usize;
+        Self {
+            map,
+            flows,
+            neutral_cost,
+            cheapest_step,
+        }
+    }
+
+    pub fn flow_at(&self, position: (usize, usize)) -> (i64, i64) {
+        self.flows[position.1 * self.map.width() + position.0]
+    }
+}
+
+// -1, 0 or 1 per axis, on wrapping maps a jump across the edge is a single step back
+fn direction(from: usize, to: usize) -> i64 {
+    let delta = to as i64 - from as i64;
+    if delta.abs() > 1 {
+        return -delta.signum();
+    }
+    delta
+}
+
+impl PathGenerator for FlowField {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.map.generate_paths(from_position)
+    }
+
+    fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> usize {
+        let flow = self.flow_at(current_position);
+        let along = flow.0 * direction(current_position.0, next_position.0)
+            + flow.1 * direction(current_position.1, next_position.1);
+        let factor = (self.neutral_cost as i64 - along).max(1) as usize;
+        self.map.calculate_cost(current_position, next_position) * factor
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> usize {
+        self.map.calculate_heuristic_cost(position, target) * self.cheapest_step
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::AStar;
+
+    // a current flowing east along row 2
+    fn river() -> FlowField {
+        let flows = (0..35)
+            .map(|cell| if cell / 7 == 2 { (3, 0) } else { (0, 0) })
+            .collect();
+        FlowField::new(GridMap::new(7, 5), flows, 4)
+    }
+
+    #[test]
+    fn test_downstream_detour() {
+        let field = river();
+        // 4 steps across the banks and 6 with the current beat 6 steps on still water
+        let path = AStar::run_result(&field, (0, 0), (Some(6), Some(0))).unwrap();
+        assert_eq!((path.path.len(), path.cost), (11, 22));
+        assert!(path.path.contains(&(3, 2)));
+
+        // upstream the river costs 7 a step and is avoided
+        let path = AStar::run_result(&field, (6, 0), (Some(0), Some(0))).unwrap();
+        assert_eq!((path.path.len(), path.cost), (7, 24));
+        assert!(path.path.iter().all(|position| position.1 == 0));
+    }
+
+    #[test]
+    fn test_heuristic_is_admissible() {
+        let field = river();
+        assert_eq!(field.cheapest_step, 1);
+        for x in 0..7 {
+            for y in 0..5 {
+                let cost = AStar::shortest_cost(&field, (x, y), (6, 2)).unwrap();
+                assert!(field.calculate_heuristic_cost((x, y), (Some(6), Some(2))) <= cost);
+            }
+        }
+    }
+}
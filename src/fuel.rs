@@ -0,0 +1,140 @@
+use crate::{AStar, Cost, PathGenerator};
+use std::collections::HashSet;
+
+// searches over (position, fuel_remaining) states, every move burns one unit of fuel and
+// arriving on a station fills the tank up to capacity again
+pub struct FuelMap<'a, T> {
+    inner: &'a T,
+    capacity: usize,
+    stations: HashSet<(usize, usize)>,
+}
+
+impl<'a, T> FuelMap<'a, T> {
+    pub fn new(inner: &'a T, capacity: usize, stations: HashSet<(usize, usize)>) -> Self {
+        assert!(
+            capacity < usize::MAX,
+            "FuelMap needs a capacity below usize::MAX"
+        );
+        Self {
+            inner,
+            capacity,
+            stations,
+        }
+    }
+
+    // a state is packed into a position as (x * (capacity + 1) + fuel, y), so the search
+    // itself needs no changes
+    fn pack(&self, position: (usize, usize), fuel: usize) -> (usize, usize) {
+        let x = position
+            .0
+            .checked_mul(self.capacity + 1)
+            .and_then(|x| x.checked_add(fuel))
+            .unwrap_or_else(|| {
+                panic!(
+                    "x = {} doesn't fit a FuelMap state with a capacity of {}",
+                    position.0, self.capacity
+                )
+            });
+        (x, position.1)
+    }
+
+    fn unpack(&self, state: (usize, usize)) -> ((usize, usize), usize) {
+        (
+            (state.0 / (self.capacity + 1), state.1),
+            state.0 % (self.capacity + 1),
+        )
+    }
+
+    // starts with a full tank, the path is ordered like in AStar::run
+    pub fn run<C: Cost>(
+        &self,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> Option<Vec<(usize, usize)>>
+    where
+        T: PathGenerator<C>,
+    {
+        let mut inst = AStar::new(target);
+        let goal = inst.search_with(
+            &FuelStates { map: self },
+            self.pack(start, self.capacity),
+            || 0,
+            |node| AStar::<C>::target_is_reached(target, &self.unpack(node.position).0),
+        )?;
+        let path = AStar::reconstruct_path(goal);
+        Some(path.into_iter().map(|state| self.unpack(state).0).collect())
+    }
+}
+
+// the packed states as positions, only seen by the search in FuelMap::run
+struct FuelStates<'b, 'a, T> {
+    map: &'b FuelMap<'a, T>,
+}
+
+impl<C: Cost, T: PathGenerator<C>> PathGenerator<C> for FuelStates<'_, '_, T> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let (position, fuel) = self.map.unpack(from_position);
+        if fuel == 0 {
+            return Vec::new();
+        }
+        self.map
+            .inner
+            .generate_paths(position)
+            .into_iter()
+            .map(|next| {
+                if self.map.stations.contains(&next) {
+                    return self.map.pack(next, self.map.capacity);
+                }
+                self.map.pack(next, fuel - 1)
+            })
+            .collect()
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.map.inner.calculate_cost(
+            self.map.unpack(current_position).0,
+            self.map.unpack(next_position).0,
+        )
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        self.map
+            .inner
+            .calculate_heuristic_cost(self.map.unpack(position).0, target)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GridMap;
+
+    #[test]
+    fn test_refuel_on_the_way() {
+        let map = GridMap::new(11, 3);
+        let target = (Some(10), Some(0));
+        assert_eq!(AStar::run(&map, (0, 0), target).unwrap().len(), 11);
+
+        let dry = FuelMap::new(&map, 7, HashSet::new());
+        assert_eq!(dry.run((0, 0), target), None);
+
+        let fuel = FuelMap::new(&map, 7, HashSet::from([(5, 2)]));
+        let path = fuel.run((0, 0), target).unwrap();
+        assert!(path.contains(&(5, 2)));
+        assert_eq!(path.len(), 15);
+        assert_eq!(path.first(), Some(&(10, 0)));
+        assert_eq!(path.last(), Some(&(0, 0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit a FuelMap state with a capacity of 7")]
+    fn test_pack_overflow_panics() {
+        let map = GridMap::new(3, 3);
+        let fuel = FuelMap::new(&map, 7, HashSet::new());
+        fuel.run((usize::MAX / 4, 0), (Some(0), Some(0)));
+    }
+}
@@ -0,0 +1,144 @@
+use crate::sweep::sweep;
+use crate::{AStar, Cost, PathGenerator};
+
+// picks among predecessors that are all on an optimal path, walking back from the goal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GoalTiePolicy {
+    // whichever one the search reached first, like run
+    #[default]
+    First,
+    // the one continuing the current direction, fewer turns without counting all of them
+    Straightest,
+    // the one with the lowest x, then the lowest y
+    Leftmost,
+}
+
+impl<C: Cost> AStar<C> {
+    // the goal is found by a normal search, then the path is rebuilt from a Dijkstra sweep
+    // over everything cheaper than it so every optimal predecessor is known
+    pub fn run_with_goal_tie_policy<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        policy: GoalTiePolicy,
+    ) -> Option<Vec<(usize, usize)>> {
+        let mut inst = Self::new(target);
+        let goal = inst.search(from_struct, start)?;
+        if policy == GoalTiePolicy::First {
+            return Some(Self::reconstruct_path(goal));
+        }
+        let goal = goal.position;
+        let settled = sweep(from_struct, start, Some(&[goal]));
+        let is_predecessor = |previous: (usize, usize), position: (usize, usize)| match (
+            settled.get(&previous),
+            settled.get(&position),
+        ) {
+            (Some(previous_node), Some(node)) => {
+                previous_node.cost + from_struct.calculate_cost(previous, position) == node.cost
+            }
+            _ => false,
+        };
+        // optimal steps that keep going from position over previous and further the same way
+        let straight_run = |position: (usize, usize), previous: (usize, usize)| {
+            let step = (
+                previous.0 as i64 - position.0 as i64,
+                previous.1 as i64 - position.1 as i64,
+            );
+            let (mut later, mut earlier) = (position, previous);
+            let mut length = 0;
+            while is_predecessor(earlier, later) {
+                length += 1;
+                let next = (earlier.0 as i64 + step.0, earlier.1 as i64 + step.1);
+                if next.0 < 0 || next.1 < 0 {
+                    break;
+                }
+                (later, earlier) = (earlier, (next.0 as usize, next.1 as usize));
+            }
+            length
+        };
+        let mut path = vec![goal];
+        let mut position = goal;
+        while position != start {
+            let candidates: Vec<(usize, usize)> = from_struct
+                .reverse_neighbors(position)
+                .into_iter()
+                .filter(|previous| !path.contains(previous) && is_predecessor(*previous, position))
+                .collect();
+            let chosen = match policy {
+                GoalTiePolicy::Straightest => {
+                    // the cell that keeps the direction of the step after position
+                    let straight_on = path.len().checked_sub(2).map(|index| {
+                        let next = path[index];
+                        (
+                            2 * position.0 as i64 - next.0 as i64,
+                            2 * position.1 as i64 - next.1 as i64,
+                        )
+                    });
+                    candidates
+                        .iter()
+                        .copied()
+                        .find(|previous| {
+                            straight_on == Some((previous.0 as i64, previous.1 as i64))
+                        })
+                        .or_else(|| {
+                            // on a turn and at the goal the longest straight stretch wins,
+                            // rev keeps the first of equally long ones
+                            candidates
+                                .iter()
+                                .copied()
+                                .rev()
+                                .max_by_key(|previous| straight_run(position, *previous))
+                        })
+                }
+                _ => candidates.iter().copied().min(),
+            }?;
+            path.push(chosen);
+            position = chosen;
+        }
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GridMap;
+
+    fn turns(path: &[(usize, usize)]) -> usize {
+        path.windows(3)
+            .filter(|steps| (steps[0].0 == steps[2].0) == (steps[0].1 == steps[2].1))
+            .count()
+    }
+
+    #[test]
+    fn test_goal_tie_policy() {
+        // the goal can be approached from (2, 3) and from (3, 2) for the same cost
+        let map = GridMap::new(4, 4);
+        let target = (Some(3), Some(3));
+        let run = |policy| AStar::run_with_goal_tie_policy(&map, (1, 0), target, policy).unwrap();
+        assert_eq!(
+            Some(run(GoalTiePolicy::First)),
+            AStar::run(&map, (1, 0), target)
+        );
+        assert_eq!(
+            run(GoalTiePolicy::Leftmost),
+            vec![(3, 3), (2, 3), (1, 3), (1, 2), (1, 1), (1, 0)]
+        );
+        // coming down column 3 runs straight for 3 steps, along row 3 only for 2
+        assert_eq!(
+            run(GoalTiePolicy::Straightest),
+            vec![(3, 3), (3, 2), (3, 1), (3, 0), (2, 0), (1, 0)]
+        );
+
+        let map = GridMap::builder(5, 5).block((2, 2)).build();
+        let straightest = AStar::run_with_goal_tie_policy(
+            &map,
+            (0, 0),
+            (Some(4), Some(4)),
+            GoalTiePolicy::Straightest,
+        )
+        .unwrap();
+        assert_eq!(straightest.len(), 9);
+        assert_eq!(turns(&straightest), 1);
+    }
+}
@@ -0,0 +1,393 @@
+use crate::line_of_sight::line_cells;
+use crate::PathGenerator;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+pub struct GridMap {
+    width: usize,
+    height: usize,
+    blocked: HashSet<(usize, usize)>,
+    diagonal: bool,
+    // cells diagonal moves can be made from when diagonal is off
+    corners: HashSet<(usize, usize)>,
+    wrap: bool,
+}
+
+impl GridMap {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            blocked: HashSet::new(),
+            diagonal: false,
+            corners: HashSet::new(),
+            wrap: false,
+        }
+    }
+
+    pub fn builder(width: usize, height: usize) -> GridMapBuilder {
+        GridMapBuilder {
+            map: Self::new(width, height),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, position: (usize, usize)) -> bool {
+        position.0 < self.width && position.1 < self.height
+    }
+
+    pub fn is_blocked(&self, position: (usize, usize)) -> bool {
+        self.blocked.contains(&position)
+    }
+
+    pub fn is_passable(&self, position: (usize, usize)) -> bool {
+        self.contains(position) && !self.is_blocked(position)
+    }
+
+    pub fn block(&mut self, position: (usize, usize)) {
+        self.blocked.insert(position);
+    }
+
+    pub fn unblock(&mut self, position: (usize, usize)) {
+        self.blocked.remove(&position);
+    }
+
+    // path is expected in the order returned by AStar::run, from the target back to the start
+    pub fn render_with_path(&self, path: &[(usize, usize)]) -> String {
+        let mut rendered = String::with_capacity((self.width + 1) * self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let position = (x, y);
+                let symbol = if path.last() == Some(&position) {
+                    'S'
+                } else if path.first() == Some(&position) {
+                    'G'
+                } else if path.contains(&position) {
+                    '*'
+                } else if self.is_blocked(position) {
+                    '#'
+                } else {
+                    '.'
+                };
+                rendered.push(symbol);
+            }
+            rendered.push('\n');
+        }
+        rendered
+    }
+
+    fn offsets(&self, position: (usize, usize)) -> &'static [(i64, i64)] {
+        if self.diagonal || self.corners.contains(&position) {
+            return &[
+                (-1, 0),
+                (0, -1),
+                (1, 0),
+                (0, 1),
+                (-1, -1),
+                (1, -1),
+                (1, 1),
+                (-1, 1),
+            ];
+        }
+        &[(-1, 0), (0, -1), (1, 0), (0, 1)]
+    }
+
+    fn offset_position(
+        &self,
+        position: (usize, usize),
+        offset: (i64, i64),
+    ) -> Option<(usize, usize)> {
+        if self.wrap {
+            let x = (position.0 as i64 + offset.0).rem_euclid(self.width as i64);
+            let y = (position.1 as i64 + offset.1).rem_euclid(self.height as i64);
+            return Some((x as usize, y as usize));
+        }
+        let x = position.0.checked_add_signed(offset.0 as isize)?;
+        let y = position.1.checked_add_signed(offset.1 as isize)?;
+        if !self.contains((x, y)) {
+            return None;
+        }
+        Some((x, y))
+    }
+}
+
+// costs are scaled onto the digits 0..=9 between the lowest and the highest one,
+// cells missing from field are rendered as '-'
+pub fn render_cost_field(
+    field: &HashMap<(usize, usize), usize>,
+    width: usize,
+    height: usize,
+) -> String {
+    let lowest = field.values().min().copied().unwrap_or(0);
+    let spread = (field.values().max().copied().unwrap_or(0) - lowest).max(1);
+    let mut rendered = String::with_capacity((width + 1) * height);
+    for y in 0..height {
+        for x in 0..width {
+            let symbol = match field.get(&(x, y)) {
+                Some(cost) => char::from_digit(((cost - lowest) * 9 / spread) as u32, 10).unwrap(),
+                None => '-',
+            };
+            rendered.push(symbol);
+        }
+        rendered.push('\n');
+    }
+    rendered
+}
+
+// blocked cells sharing a side with a cell of path, in the order the path runs past them
+pub fn path_adjacent_obstacles(map: &GridMap, path: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut obstacles = Vec::new();
+    for position in path {
+        for offset in [(-1, 0), (0, -1), (1, 0), (0, 1)] {
+            if let Some(next) = map.offset_position(*position, offset) {
+                if map.is_blocked(next) && !obstacles.contains(&next) {
+                    obstacles.push(next);
+                }
+            }
+        }
+    }
+    obstacles
+}
+
+impl fmt::Display for GridMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render_with_path(&[]))
+    }
+}
+
+// there is no FromIterator, a map can't be built from blocked cells alone without its size
+impl Extend<(usize, usize)> for GridMap {
+    fn extend<I: IntoIterator<Item = (usize, usize)>>(&mut self, iter: I) {
+        self.blocked.extend(iter);
+    }
+}
+
+impl PathGenerator for GridMap {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut possible_paths = Vec::new();
+        for offset in self.offsets(from_position) {
+            if let Some(position) = self.offset_position(from_position, *offset) {
+                // on narrow wrapping maps several offsets can land on the same cell
+                if position != from_position
+                    && !self.is_blocked(position)
+                    && !possible_paths.contains(&position)
+                {
+                    possible_paths.push(position);
+                }
+            }
+        }
+        possible_paths
+    }
+
+    #[allow(unused_variables)]
+    fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> usize {
+        1
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> usize {
+        let (dx, dy) = if self.wrap {
+            // targets outside of the map are wrapped onto it as well
+            let dx = target
+                .0
+                .map_or(0, |x| (x % self.width).abs_diff(position.0 % self.width));
+            let dy = target
+                .1
+                .map_or(0, |y| (y % self.height).abs_diff(position.1 % self.height));
+            (dx.min(self.width - dx), dy.min(self.height - dy))
+        } else {
+            (
+                target.0.map_or(0, |x| x.abs_diff(position.0)),
+                target.1.map_or(0, |y| y.abs_diff(position.1)),
+            )
+        };
+        // a single corner already allows diagonal shortcuts
+        if self.diagonal || !self.corners.is_empty() {
+            return dx.max(dy);
+        }
+        dx + dy
+    }
+
+    // without diagonal moves only straight rows and columns are lines of sight
+    fn line_of_sight(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        if self.wrap || !(self.diagonal || from.0 == to.0 || from.1 == to.1) {
+            return false;
+        }
+        line_cells(from, to)
+            .into_iter()
+            .all(|position| self.is_passable(position))
+    }
+}
+
+pub struct GridMapBuilder {
+    map: GridMap,
+}
+
+impl GridMapBuilder {
+    pub fn diagonal(mut self, diagonal: bool) -> Self {
+        self.map.diagonal = diagonal;
+        self
+    }
+
+    pub fn corners<I: IntoIterator<Item = (usize, usize)>>(mut self, positions: I) -> Self {
+        self.map.corners.extend(positions);
+        self
+    }
+
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        assert!(
+            !wrap || (self.map.width > 0 && self.map.height > 0),
+            "a wrapping GridMap needs a non zero width and height"
+        );
+        self.map.wrap = wrap;
+        self
+    }
+
+    pub fn block(mut self, position: (usize, usize)) -> Self {
+        self.map.block(position);
+        self
+    }
+
+    pub fn block_all<I: IntoIterator<Item = (usize, usize)>>(mut self, positions: I) -> Self {
+        self.map.extend(positions);
+        self
+    }
+
+    pub fn build(self) -> GridMap {
+        self.map
+    }
+}
+
+impl Extend<(usize, usize)> for GridMapBuilder {
+    fn extend<I: IntoIterator<Item = (usize, usize)>>(&mut self, iter: I) {
+        self.map.extend(iter);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::AStar;
+
+    #[test]
+    fn test_builder_and_extend() {
+        let mut builder = GridMap::builder(4, 4)
+            .block((1, 0))
+            .block_all([(1, 1), (1, 2)]);
+        builder.extend([(3, 2)]);
+        let mut map = builder.build();
+        assert!(map.is_blocked((1, 2)) && map.is_blocked((3, 2)));
+        let path = AStar::run(&map, (0, 0), (Some(2), Some(0))).unwrap();
+        assert!(path.iter().all(|position| map.is_passable(*position)));
+        assert_eq!(path.len(), 9);
+
+        map.extend([(1, 3)]);
+        assert_eq!(AStar::run(&map, (0, 0), (Some(2), Some(0))), None);
+    }
+
+    #[test]
+    fn test_diagonal_and_unblock() {
+        let mut map = GridMap::builder(3, 3).diagonal(true).block((1, 0)).build();
+        assert_eq!(map.generate_paths((0, 0)), vec![(0, 1), (1, 1)]);
+        assert_eq!(
+            AStar::run(&map, (0, 0), (Some(2), Some(2))).unwrap().len(),
+            3
+        );
+
+        map.unblock((1, 0));
+        assert!(map.is_passable((1, 0)));
+        assert_eq!(map.generate_paths((0, 0)), vec![(1, 0), (0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn test_corners() {
+        let map = GridMap::builder(5, 3).corners([(2, 1)]).build();
+        assert_eq!(map.generate_paths((1, 1)).len(), 4);
+        assert_eq!(map.generate_paths((2, 1)).len(), 8);
+        let path = AStar::run(&map, (0, 1), (Some(3), Some(2))).unwrap();
+        assert_eq!(path, vec![(3, 2), (2, 1), (1, 1), (0, 1)]);
+        // without a corner on the way the path stays orthogonal
+        let path = AStar::run(&map, (0, 0), (Some(1), Some(2))).unwrap();
+        assert_eq!(path.len(), 4);
+        assert!(!path.contains(&(2, 1)));
+    }
+
+    #[test]
+    fn test_render_with_path() {
+        let map = GridMap::builder(4, 3).block_all([(1, 0), (1, 1)]).build();
+        let path = AStar::run(&map, (0, 0), (Some(2), Some(0))).unwrap();
+        assert_eq!(map.render_with_path(&path), "S#G.\n*#*.\n***.\n");
+        assert_eq!(map.to_string(), ".#..\n.#..\n....\n");
+    }
+
+    #[test]
+    fn test_wrap() {
+        let bounded = GridMap::new(10, 3);
+        let torus = GridMap::builder(10, 3).wrap(true).build();
+        let target = (Some(8), Some(2));
+        assert_eq!(bounded.calculate_heuristic_cost((1, 0), target), 9);
+        assert_eq!(torus.calculate_heuristic_cost((1, 0), target), 4);
+
+        assert_eq!(AStar::run(&bounded, (1, 0), target).unwrap().len(), 10);
+        let path = AStar::run(&torus, (1, 0), target).unwrap();
+        assert_eq!(path.len(), 5);
+        // crosses both the left/right and top/bottom boundaries
+        assert!(path.iter().any(|position| position.0 == 9));
+        assert!(!path.iter().any(|position| position.1 == 1));
+    }
+
+    #[test]
+    fn test_wrap_target_outside_of_map() {
+        let torus = GridMap::builder(10, 3).wrap(true).build();
+        assert_eq!(
+            torus.calculate_heuristic_cost((1, 0), (Some(18), Some(5))),
+            4
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "non zero width and height")]
+    fn test_wrap_rejects_empty_map() {
+        GridMap::builder(0, 3).wrap(true);
+    }
+
+    #[test]
+    fn test_render_cost_field() {
+        let map = GridMap::builder(4, 3)
+            .block_all([(1, 0), (1, 1), (1, 2)])
+            .build();
+        let field = AStar::cost_field(&map, (0, 0));
+        assert_eq!(render_cost_field(&field, 4, 3), "0---\n4---\n9---\n");
+
+        let field = AStar::cost_field(&GridMap::new(4, 3), (1, 1));
+        assert_eq!(render_cost_field(&field, 4, 3), "6369\n3036\n6369\n");
+    }
+
+    #[test]
+    fn test_path_adjacent_obstacles() {
+        // the path runs along the wall on row 1, the wall on row 4 is out of reach
+        let map = GridMap::builder(5, 5)
+            .block_all([(1, 1), (2, 1), (3, 1), (1, 4), (2, 4)])
+            .build();
+        let path = AStar::run(&map, (0, 0), (Some(4), Some(0))).unwrap();
+        assert_eq!(path.len(), 5);
+        let obstacles = path_adjacent_obstacles(&map, &path);
+        assert_eq!(obstacles.len(), 3);
+        assert!(obstacles.iter().all(|position| position.1 == 1));
+        assert!(path_adjacent_obstacles(&map, &[]).is_empty());
+    }
+}
@@ -0,0 +1,84 @@
+use crate::PathGenerator;
+
+// makes stepping onto cells within radius of hazard more expensive, penalty on it and
+// falling off linearly with the Chebyshev distance, borrowed so a moving hazard is cheap to
+// set up again for every search
+pub struct HazardField<'a, T> {
+    inner: &'a T,
+    hazard: (usize, usize),
+    radius: usize,
+    penalty: usize,
+}
+
+impl<'a, T> HazardField<'a, T> {
+    pub fn new(inner: &'a T, hazard: (usize, usize), radius: usize, penalty: usize) -> Self {
+        Self {
+            inner,
+            hazard,
+            radius,
+            penalty,
+        }
+    }
+
+    pub fn extra_cost(&self, position: (usize, usize)) -> usize {
+        let distance = position
+            .0
+            .abs_diff(self.hazard.0)
+            .max(position.1.abs_diff(self.hazard.1));
+        if distance > self.radius {
+            return 0;
+        }
+        self.penalty * (self.radius + 1 - distance) / (self.radius + 1)
+    }
+}
+
+impl<T: PathGenerator> PathGenerator for HazardField<'_, T> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner.generate_paths(from_position)
+    }
+
+    fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> usize {
+        self.inner.calculate_cost(current_position, next_position) + self.extra_cost(next_position)
+    }
+
+    // costs only grow, so the heuristic of inner stays admissible
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> usize {
+        self.inner.calculate_heuristic_cost(position, target)
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: usize) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_path_arcs_around_hazard() {
+        let map = GridMap::new(9, 7);
+        let target = (Some(8), Some(3));
+        let direct = AStar::run(&map, (0, 3), target).unwrap();
+        assert!(direct.contains(&(4, 3)));
+
+        let hazard = HazardField::new(&map, (4, 3), 2, 12);
+        assert_eq!(hazard.extra_cost((4, 3)), 12);
+        assert_eq!(hazard.extra_cost((6, 1)), 4);
+        assert_eq!(hazard.extra_cost((7, 3)), 0);
+        let path = AStar::run(&hazard, (0, 3), target).unwrap();
+        assert!(path
+            .iter()
+            .all(|position| position.0.abs_diff(4).max(position.1.abs_diff(3)) > 1));
+        assert!(path.len() > direct.len());
+    }
+}
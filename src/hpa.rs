@@ -0,0 +1,281 @@
+use crate::{AStar, GridMap, PathGenerator, PathResult, SearchStats, SubGrid};
+use std::collections::HashMap;
+
+type Edges = HashMap<(usize, usize), Vec<((usize, usize), usize)>>;
+type Paths = HashMap<((usize, usize), (usize, usize)), Vec<(usize, usize)>>;
+
+// map cut into cluster_size x cluster_size clusters, every stretch of border two clusters
+// share gets one crossing in its middle and the crossings of a cluster are joined by their
+// paths inside it. Searches go over the crossings and are turned into cells at the end, so
+// paths can be longer than the shortest one
+pub struct HpaStar<'a> {
+    map: &'a GridMap,
+    cluster_size: usize,
+    crossings: HashMap<(usize, usize), Vec<(usize, usize)>>,
+    edges: Edges,
+    // keyed by (from, to) and ordered like the path returned by run, from to back to from
+    paths: Paths,
+}
+
+impl<'a> HpaStar<'a> {
+    pub fn new(map: &'a GridMap, cluster_size: usize) -> Self {
+        assert!(cluster_size > 0, "HpaStar needs a non zero cluster size");
+        let mut hpa = Self {
+            map,
+            cluster_size,
+            crossings: HashMap::new(),
+            edges: HashMap::new(),
+            paths: HashMap::new(),
+        };
+        for (from, to) in hpa.border_crossings() {
+            hpa.edges
+                .entry(from)
+                .or_default()
+                .push((to, map.calculate_cost(from, to)));
+            hpa.edges
+                .entry(to)
+                .or_default()
+                .push((from, map.calculate_cost(to, from)));
+            for position in [from, to] {
+                let cells = hpa.crossings.entry(hpa.cluster_of(position)).or_default();
+                if !cells.contains(&position) {
+                    cells.push(position);
+                }
+            }
+        }
+        let mut edges = Edges::new();
+        let mut paths = Paths::new();
+        for cells in hpa.crossings.values() {
+            for from in cells {
+                for to in cells.iter().filter(|to| *to != from) {
+                    if let Some(result) = hpa.search_in_cluster(*from, *to) {
+                        edges.entry(*from).or_default().push((*to, result.cost));
+                        paths.insert((*from, *to), result.path);
+                    }
+                }
+            }
+        }
+        for (position, cluster_edges) in edges {
+            hpa.edges.entry(position).or_default().extend(cluster_edges);
+        }
+        hpa.paths = paths;
+        hpa
+    }
+
+    // stats only count the searches made on the cells of map to join start and goal to the
+    // crossings, the search over the crossings is not included
+    pub fn find_path(&self, start: (usize, usize), goal: (usize, usize)) -> Option<PathResult> {
+        let mut stats = SearchStats::default();
+        let mut extra_edges = Edges::new();
+        let mut extra_paths = Paths::new();
+        let mut connect = |from: (usize, usize), to: (usize, usize)| {
+            if let Some(result) = self.search_in_cluster(from, to) {
+                stats.expanded += result.stats.expanded;
+                stats.generated += result.stats.generated;
+                stats.reopened += result.stats.reopened;
+                extra_edges.entry(from).or_default().push((to, result.cost));
+                extra_paths.insert((from, to), result.path);
+            }
+        };
+        let no_crossings = Vec::new();
+        let cluster_crossings = |position| {
+            self.crossings
+                .get(&self.cluster_of(position))
+                .unwrap_or(&no_crossings)
+        };
+        for crossing in cluster_crossings(start) {
+            if *crossing != start {
+                connect(start, *crossing);
+            }
+        }
+        for crossing in cluster_crossings(goal) {
+            if *crossing != goal {
+                connect(*crossing, goal);
+            }
+        }
+        if start != goal && self.cluster_of(start) == self.cluster_of(goal) {
+            connect(start, goal);
+        }
+
+        let graph = Crossings {
+            hpa: self,
+            extra_edges: &extra_edges,
+        };
+        let crossing_path = AStar::run(&graph, start, (Some(goal.0), Some(goal.1)))?;
+        let mut path = vec![goal];
+        let mut cost = 0;
+        for pair in crossing_path.windows(2) {
+            let (to, from) = (pair[0], pair[1]);
+            cost += graph.calculate_cost(from, to);
+            match extra_paths
+                .get(&(from, to))
+                .or_else(|| self.paths.get(&(from, to)))
+            {
+                Some(segment) => path.extend(&segment[1..]),
+                // a step from one cluster into the next
+                None => path.push(from),
+            }
+        }
+        stats.path_len = path.len() - 1;
+        Some(PathResult {
+            path,
+            cost,
+            stats,
+            optimal: false,
+            seed: None,
+        })
+    }
+
+    fn cluster_of(&self, position: (usize, usize)) -> (usize, usize) {
+        (
+            position.0 / self.cluster_size,
+            position.1 / self.cluster_size,
+        )
+    }
+
+    // pairs of passable cells facing each other across a border, None ends a stretch
+    fn border_crossings(&self) -> Vec<((usize, usize), (usize, usize))> {
+        let size = self.cluster_size;
+        let (width, height) = (self.map.width(), self.map.height());
+        let mut pairs = Vec::new();
+        let mut face = |from: (usize, usize), to: (usize, usize), along: usize| {
+            if along % size == 0 {
+                pairs.push(None);
+            }
+            let passable = self.map.is_passable(from) && self.map.is_passable(to);
+            pairs.push(passable.then_some((from, to)));
+        };
+        for x in (size..width).step_by(size) {
+            for y in 0..height {
+                face((x - 1, y), (x, y), y);
+            }
+        }
+        for y in (size..height).step_by(size) {
+            for x in 0..width {
+                face((x, y - 1), (x, y), x);
+            }
+        }
+        pairs
+            .split(Option::is_none)
+            .filter(|stretch| !stretch.is_empty())
+            .filter_map(|stretch| stretch[stretch.len() / 2])
+            .collect()
+    }
+
+    // None when the cells are in different clusters or to can't be reached without leaving it
+    fn search_in_cluster(&self, from: (usize, usize), to: (usize, usize)) -> Option<PathResult> {
+        let cluster = self.cluster_of(from);
+        let origin = (cluster.0 * self.cluster_size, cluster.1 * self.cluster_size);
+        let window = SubGrid::new(
+            self.map,
+            origin,
+            self.cluster_size.min(self.map.width() - origin.0),
+            self.cluster_size.min(self.map.height() - origin.1),
+        );
+        let target = window.from_inner(to)?;
+        let mut result = AStar::run_result(
+            &window,
+            window.from_inner(from)?,
+            (Some(target.0), Some(target.1)),
+        )?;
+        result.path = result
+            .path
+            .into_iter()
+            .map(|position| window.to_inner(position))
+            .collect();
+        Some(result)
+    }
+}
+
+struct Crossings<'a, 'b> {
+    hpa: &'b HpaStar<'a>,
+    extra_edges: &'b Edges,
+}
+
+impl Crossings<'_, '_> {
+    fn edges_from(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = &((usize, usize), usize)> {
+        self.hpa
+            .edges
+            .get(&position)
+            .into_iter()
+            .chain(self.extra_edges.get(&position))
+            .flatten()
+    }
+}
+
+impl PathGenerator for Crossings<'_, '_> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.edges_from(from_position).map(|edge| edge.0).collect()
+    }
+
+    fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> usize {
+        self.edges_from(current_position)
+            .filter(|edge| edge.0 == next_position)
+            .map(|edge| edge.1)
+            .min()
+            .expect("cost requested for a missing crossing edge")
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> usize {
+        self.hpa.map.calculate_heuristic_cost(position, target)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_walkable(map: &GridMap, path: &[(usize, usize)]) {
+        for pair in path.windows(2) {
+            assert!(map.generate_paths(pair[1]).contains(&pair[0]));
+        }
+    }
+
+    #[test]
+    fn test_large_map() {
+        // walls every 20 columns with a single gap each
+        let mut map = GridMap::new(100, 100);
+        for x in (20..100).step_by(20) {
+            for y in (0..100).filter(|y| *y != x * 7 % 100) {
+                map.block((x, y));
+            }
+        }
+        let target = (Some(99), Some(99));
+        let (optimal, stats) = AStar::run_with_stats(&map, (0, 0), target);
+        let optimal_cost = optimal.unwrap().len() - 1;
+
+        let hpa = HpaStar::new(&map, 10);
+        let result = hpa.find_path((0, 0), (99, 99)).unwrap();
+        assert_eq!(result.path.first(), Some(&(99, 99)));
+        assert_eq!(result.path.last(), Some(&(0, 0)));
+        assert_walkable(&map, &result.path);
+        assert_eq!(result.cost, result.path.len() - 1);
+        assert!(result.cost as f64 <= optimal_cost as f64 * 1.2);
+        assert!(result.stats.expanded * 10 < stats.expanded);
+    }
+
+    #[test]
+    fn test_same_cluster_and_unreachable() {
+        let mut map = GridMap::new(8, 8);
+        let hpa = HpaStar::new(&map, 4);
+        let result = hpa.find_path((0, 0), (2, 3)).unwrap();
+        assert_eq!(result.cost, 5);
+        assert_walkable(&map, &result.path);
+        assert_eq!(hpa.find_path((5, 5), (5, 5)).unwrap().path, vec![(5, 5)]);
+
+        map.extend((0..8).map(|y| (4, y)));
+        let hpa = HpaStar::new(&map, 4);
+        assert!(hpa.find_path((0, 0), (7, 7)).is_none());
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{AStar, Cost, GridMap, Node, PathGenerator};
+use std::rc::Rc;
+
+// maps every position a search can reach to a distinct index below domain_size, so the
+// closed nodes can be kept in an array instead of being looked up among all of them
+pub trait Indexable {
+    fn to_index(&self, position: (usize, usize)) -> usize;
+    fn domain_size(&self) -> usize;
+}
+
+impl Indexable for GridMap {
+    fn to_index(&self, position: (usize, usize)) -> usize {
+        position.1 * self.width() + position.0
+    }
+
+    fn domain_size(&self) -> usize {
+        self.width() * self.height()
+    }
+}
+
+impl<C: Cost> AStar<C> {
+    // run with the closed nodes in an array of domain_size slots, same path as run
+    pub fn run_indexed<T: PathGenerator<C> + Indexable>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> Option<Vec<(usize, usize)>> {
+        let mut inst = Self::new(target);
+        let goal = inst.search_indexed(from_struct, start)?;
+        Some(Self::reconstruct_path(goal))
+    }
+
+    fn search_indexed<T: PathGenerator<C> + Indexable>(
+        &mut self,
+        from_struct: &T,
+        start: (usize, usize),
+    ) -> Option<Rc<Node<C>>> {
+        let target = self.target;
+        let domain_size = from_struct.domain_size();
+        self.closed_slots.resize_with(domain_size, || None);
+        self.search_with_index(
+            from_struct,
+            start,
+            || 0,
+            |node| Self::target_is_reached(target, &node.position),
+            |position| {
+                let index = from_struct.to_index(position);
+                assert!(
+                    index < domain_size,
+                    "to_index maps {position:?} to {index}, outside of a domain of {domain_size}"
+                );
+                Some(index)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::Graph;
+
+    impl Indexable for Graph {
+        fn to_index(&self, position: (usize, usize)) -> usize {
+            position.0
+        }
+
+        // too small for the node 2
+        fn domain_size(&self) -> usize {
+            2
+        }
+    }
+
+    #[test]
+    fn test_grid_index() {
+        let map = GridMap::new(7, 3);
+        assert_eq!(map.domain_size(), 21);
+        assert_eq!(map.to_index((0, 0)), 0);
+        assert_eq!(map.to_index((6, 0)), 6);
+        assert_eq!(map.to_index((2, 1)), 9);
+        assert_eq!(map.to_index((6, 2)), 20);
+    }
+
+    #[test]
+    fn test_indexed_matches_hashed() {
+        let mut map = GridMap::new(60, 60);
+        map.extend((0..55).map(|y| (30, y)));
+        let target = (Some(59), Some(0));
+
+        let path = AStar::run(&map, (0, 0), target);
+        assert!(path.is_some());
+        assert_eq!(AStar::run_indexed(&map, (0, 0), target), path);
+
+        // every expanded node sits in its slot, none is searched for among the closed nodes
+        let mut inst = AStar::new(target);
+        inst.search_indexed(&map, (0, 0)).unwrap();
+        assert!(inst.closed_nodes.is_empty());
+        assert_eq!(inst.closed_slots.iter().flatten().count(), inst.expanded);
+        for (index, slot) in inst.closed_slots.iter().enumerate() {
+            assert!(slot
+                .as_ref()
+                .is_none_or(|node| map.to_index(node.position) == index));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "to_index maps (2, 0) to 2, outside of a domain of 2")]
+    fn test_out_of_domain_index_panics() {
+        let graph = Graph::new(vec![(0, 1, 1), (1, 2, 1)]);
+        AStar::run_indexed(&graph, (0, 0), (Some(2), Some(0)));
+    }
+}
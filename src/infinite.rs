@@ -0,0 +1,120 @@
+use crate::{AStar, Cost, PathGenerator};
+
+// keeps the neighbours within radius of start, measured as Chebyshev distance
+struct Bounded<'a, T> {
+    inner: &'a T,
+    start: (usize, usize),
+    radius: usize,
+}
+
+impl<C: Cost, T: PathGenerator<C>> PathGenerator<C> for Bounded<'_, T> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut possible_paths = self.inner.generate_paths(from_position);
+        possible_paths.retain(|position| {
+            position
+                .0
+                .abs_diff(self.start.0)
+                .max(position.1.abs_diff(self.start.1))
+                <= self.radius
+        });
+        possible_paths
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.inner.calculate_cost(current_position, next_position)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        self.inner.calculate_heuristic_cost(position, target)
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+impl<C: Cost> AStar<C> {
+    // for generators without bounds, a target that isn't reached within max_radius of start gives None
+    pub fn run_infinite<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        max_radius: usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        let bounded = Bounded {
+            inner: from_struct,
+            start,
+            radius: max_radius,
+        };
+        AStar::run(&bounded, start, target)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AStar, PathGenerator};
+
+    // every cell is open except a ring around walled_in
+    struct Endless {
+        walled_in: (usize, usize),
+    }
+
+    impl PathGenerator for Endless {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            let (x, y) = from_position;
+            [
+                x.checked_sub(1).map(|x| (x, y)),
+                y.checked_sub(1).map(|y| (x, y)),
+                Some((x + 1, y)),
+                Some((x, y + 1)),
+            ]
+            .into_iter()
+            .flatten()
+            .filter(|position| {
+                let distance =
+                    position.0.abs_diff(self.walled_in.0) + position.1.abs_diff(self.walled_in.1);
+                distance != 1
+            })
+            .collect()
+        }
+
+        #[allow(unused_variables)]
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            1
+        }
+
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            target.0.map_or(0, |x| x.abs_diff(position.0))
+                + target.1.map_or(0, |y| y.abs_diff(position.1))
+        }
+    }
+
+    #[test]
+    fn test_bounded_exploration() {
+        let world = Endless {
+            walled_in: (110, 100),
+        };
+        let path = AStar::run_infinite(&world, (100, 100), (Some(104), Some(103)), 5).unwrap();
+        assert_eq!(path.len(), 8);
+        assert_eq!(
+            AStar::run_infinite(&world, (100, 100), (Some(106), Some(100)), 5),
+            None
+        );
+        assert_eq!(
+            AStar::run_infinite(&world, (100, 100), (Some(110), Some(100)), 20),
+            None
+        );
+    }
+}
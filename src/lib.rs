@@ -1,263 +1,1342 @@
 use std::cmp::{Eq, Ord, Ordering, PartialEq, PartialOrd};
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-pub trait PathGenerator {
+mod adjacent;
+mod ara;
+#[cfg(feature = "async")]
+mod async_search;
+mod avoid;
+mod batch;
+mod builder;
+mod capacity;
+mod clearance;
+mod closed;
+mod congestion;
+mod connectivity;
+mod const_grid;
+mod context;
+mod corridor;
+mod cost;
+mod detour;
+mod directional;
+mod elevation;
+mod encoding;
+mod error;
+mod flow;
+mod fuel;
+mod goal_tie;
+mod grid;
+mod hazard;
+mod hpa;
+mod indexed;
+mod infinite;
+mod line_of_sight;
+mod max_heuristic;
+mod memory;
+mod multi_mode;
+mod navigation;
+mod no_u_turn;
+mod offset;
+mod orienteering;
+mod parallel;
+mod path;
+mod quantized;
+#[cfg(feature = "rand")]
+mod random;
+mod result;
+mod reverse;
+mod safe;
+mod stats;
+mod subgrid;
+mod sweep;
+mod teleporter;
+mod terrain;
+mod tie_break;
+mod trace;
+mod turns;
+mod until;
+mod validate;
+mod world;
+
+pub use ara::{AraStar, Convergence};
+#[cfg(feature = "async")]
+pub use async_search::AsyncPathGenerator;
+pub use builder::AStarBuilder;
+pub use capacity::{CapacityMap, Demand};
+pub use clearance::path_min_clearance;
+pub use closed::{ClosedSet, DenseClosedSet};
+pub use congestion::{betweenness, CongestionMap};
+pub use connectivity::{critical_cells, ConnectivityIndex};
+pub use const_grid::ConstGrid;
+pub use context::SearchContext;
+pub use cost::{Cost, LexCost, MilliCost};
+pub use directional::DirectionalMap;
+pub use elevation::ElevationMap;
+pub use encoding::{decode_grid_path, encode_grid_path};
+pub use error::PathError;
+pub use flow::FlowField;
+pub use fuel::FuelMap;
+pub use goal_tie::GoalTiePolicy;
+pub use grid::{path_adjacent_obstacles, render_cost_field, GridMap, GridMapBuilder};
+pub use hazard::HazardField;
+pub use hpa::HpaStar;
+pub use indexed::Indexable;
+pub use max_heuristic::MaxHeuristic;
+pub use memory::estimated_memory;
+pub use multi_mode::{Mode, MultiModeMap};
+pub use navigation::{navigation_graph, NavigationGraph};
+pub use offset::OffsetMap;
+pub use path::{
+    compress_path, decompress_path, extract_corners, is_edge_on_path, reverse_path_in_place,
+    split_path_at_cost, Step,
+};
+pub use quantized::{FloatPathGenerator, Quantized};
+pub use result::PathResult;
+pub use stats::{effective_branching_factor, SearchStats};
+pub use subgrid::SubGrid;
+pub use teleporter::Teleporter;
+pub use terrain::TerrainGrid;
+pub use tie_break::TieBreak;
+pub use trace::SearchTrace;
+pub use validate::{validate_generator, GeneratorWarning};
+pub use world::path_to_world;
+
+// offset between two positions, (dx, dy)
+pub type Direction = (i64, i64);
+
+pub trait PathGenerator<C: Cost = usize> {
     fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)>;
     fn calculate_heuristic_cost(
         &self,
         position: (usize, usize),
         target: (Option<usize>, Option<usize>),
-    ) -> usize;
-    fn calculate_cost(
-        &self,
-        current_position: (usize, usize),
-        next_position: (usize, usize),
-    ) -> usize;
-}
-
-enum NextNodeResult<T> {
-    Ok(T),
-    Finished,
+    ) -> C;
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C;
+    // positions that list to_position among their paths, only needs overriding on directed maps
+    fn reverse_neighbors(&self, to_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.generate_paths(to_position)
+    }
+    // skips position when reached with g_cost, pruning a node that is on the optimal path
+    // makes the search return a worse path or none at all
+    #[allow(unused_variables)]
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        false
+    }
+    // true only when the Bresenham line from `from` to `to` is itself an optimal path,
+    // AStar::run_to then returns it without searching
+    #[allow(unused_variables)]
+    fn line_of_sight(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        false
+    }
 }
 
-pub struct AStar {
+pub struct AStar<C: Cost = usize> {
     target: (Option<usize>, Option<usize>),
-    que: Vec<Node>,
-    closed_nodes: Vec<Rc<Node>>,
+    que: Vec<Node<C>>,
+    closed_nodes: Vec<Rc<Node<C>>>,
+    closed_set: Box<dyn ClosedSet>,
+    // closed nodes by Indexable::to_index, empty unless the search is indexed
+    closed_slots: Vec<Option<Rc<Node<C>>>>,
+    // width and height of the grid whose cells search_with closes in closed_slots
+    dense_grid: Option<(usize, usize)>,
+    preferred_positions: HashSet<(usize, usize)>,
+    tie_break_mode: TieBreak,
+    reopen_threshold: Option<C>,
+    seed_costs: HashMap<(usize, usize), C>,
+    // added to the cost of every step on top of calculate_cost
+    action_cost: C,
+    max_closed: Option<usize>,
+    edge_time_budget: Option<Duration>,
+    timeout: Option<Duration>,
+    // the time edge_time_budget and timeout are measured in
+    clock: fn() -> Instant,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    // nodes still allowed to be popped, taken over from and handed back to the caller
+    pop_budget: Option<usize>,
+    // set when the search was stopped by timeout or cancel_flag
+    error: Option<PathError>,
+    trace: Option<SearchTrace>,
+    discovered: HashSet<(usize, usize)>,
+    expanded: usize,
+    generated: usize,
+    reopened: usize,
+    slow_edges: usize,
+    #[cfg(feature = "profiling")]
+    time_in_ordering: Duration,
+    #[cfg(feature = "profiling")]
+    time_in_expansion: Duration,
 }
 
-impl AStar {
+impl<C: Cost> AStar<C> {
     fn new(target: (Option<usize>, Option<usize>)) -> Self {
         Self {
             target,
             que: Vec::new(),
             closed_nodes: Vec::new(),
+            closed_set: Box::new(HashSet::new()),
+            closed_slots: Vec::new(),
+            dense_grid: None,
+            preferred_positions: HashSet::new(),
+            tie_break_mode: TieBreak::default(),
+            reopen_threshold: None,
+            seed_costs: HashMap::new(),
+            action_cost: C::default(),
+            max_closed: None,
+            edge_time_budget: None,
+            timeout: None,
+            clock: Instant::now,
+            cancel_flag: None,
+            pop_budget: None,
+            error: None,
+            trace: None,
+            discovered: HashSet::new(),
+            expanded: 0,
+            generated: 0,
+            reopened: 0,
+            slow_edges: 0,
+            #[cfg(feature = "profiling")]
+            time_in_ordering: Duration::ZERO,
+            #[cfg(feature = "profiling")]
+            time_in_expansion: Duration::ZERO,
         }
     }
 
-    pub fn run<T: PathGenerator>(
+    // the path goes from the target back to the start
+    pub fn run<T: PathGenerator<C>>(
         from_struct: &T,
         start: (usize, usize),
         target: (Option<usize>, Option<usize>),
     ) -> Option<Vec<(usize, usize)>> {
-        // PathGenerator is used to build possible paths
         let mut inst = Self::new(target);
-        let exposed_struct = from_struct;
-        inst.que.push(Node::new(
+        let goal = inst.search(from_struct, start)?;
+        Some(Self::reconstruct_path(goal))
+    }
+
+    // the heuristic the search starts from, a fixed denominator for progress estimates toward
+    // one target that can be computed once and reused
+    pub fn initial_heuristic<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        from_struct.calculate_heuristic_cost(start, target)
+    }
+
+    // run with the path from start to target
+    pub fn run_ordered<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> Option<Vec<(usize, usize)>> {
+        let mut path = Self::run(from_struct, start, target)?;
+        reverse_path_in_place(&mut path);
+        Some(path)
+    }
+
+    // like every partial target the cheapest cell of the row is found, the search ends when a
+    // cell of it is popped, which is the first of the path
+    pub fn run_to_row<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        row: usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        Self::run(from_struct, start, (None, Some(row)))
+    }
+
+    pub fn run_to_column<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        column: usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        Self::run(from_struct, start, (Some(column), None))
+    }
+
+    // unlike run, the steps are ordered from start to target
+    pub fn run_with_step_costs<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> Option<Vec<((usize, usize), C)>> {
+        let mut inst = Self::new(target);
+        let goal = inst.search(from_struct, start)?;
+        let mut steps = Self::reconstruct_step_costs(goal);
+        steps.reverse();
+        Some(steps)
+    }
+
+    pub fn run_coherent<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        previous_path: &[(usize, usize)],
+    ) -> Option<Vec<(usize, usize)>> {
+        // cells of previous_path win ties in total cost, costs themselves are untouched
+        let mut inst = Self::new(target);
+        inst.preferred_positions = previous_path.iter().copied().collect();
+        let goal = inst.search(from_struct, start)?;
+        Some(Self::reconstruct_path(goal))
+    }
+
+    // the path is handed to sink one position at a time in the order of run, so it can go into
+    // a reused buffer or be processed without an own Vec, returns false when there is no path
+    pub fn run_into<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        sink: impl FnMut((usize, usize)),
+    ) -> bool {
+        let mut inst = Self::new(target);
+        match inst.search(from_struct, start) {
+            Some(goal) => {
+                Self::reconstruct_into(&goal, sink);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // seed holds cells whose optimal cost from start is known, routes reaching them for more are
+    // dropped, so a seed that is lower than the real optimum makes the search miss paths
+    pub fn run_with_seed_costs<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        seed: &HashMap<(usize, usize), C>,
+    ) -> Option<Vec<(usize, usize)>> {
+        let mut inst = Self::new(target);
+        inst.seed_costs = seed.clone();
+        let goal = inst.search(from_struct, start)?;
+        Some(Self::reconstruct_path(goal))
+    }
+
+    // every node popped takes one from budget, whatever is left stays there for the next
+    // search, PathError::BudgetExhausted when it runs out before the target is reached
+    pub fn run_with_budget_counter<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        budget: &mut usize,
+    ) -> Result<Vec<(usize, usize)>, PathError> {
+        let mut inst = Self::new(target);
+        inst.pop_budget = Some(*budget);
+        let goal = inst.search(from_struct, start);
+        *budget = inst.pop_budget.unwrap_or(0);
+        match goal {
+            Some(goal) => Ok(Self::reconstruct_path(goal)),
+            None => Err(inst.error.unwrap_or(PathError::Unreachable)),
+        }
+    }
+
+    fn search<T: PathGenerator<C>>(
+        &mut self,
+        from_struct: &T,
+        start: (usize, usize),
+    ) -> Option<Rc<Node<C>>> {
+        let target = self.target;
+        let lifo = self.tie_break_mode == TieBreak::Lifo;
+        let mut sequence = u64::MAX;
+        self.search_with(
+            from_struct,
             start,
-            exposed_struct.calculate_heuristic_cost(start, target),
-        ));
+            move || {
+                if lifo {
+                    sequence -= 1;
+                }
+                sequence
+            },
+            |node| Self::target_is_reached(target, &node.position),
+        )
+    }
+
+    // draw_tie_break orders nodes left equal by total cost and preferred positions,
+    // is_goal replaces the target check while the heuristic is still computed toward the target
+    fn search_with<T, D, G>(
+        &mut self,
+        from_struct: &T,
+        start: (usize, usize),
+        draw_tie_break: D,
+        is_goal: G,
+    ) -> Option<Rc<Node<C>>>
+    where
+        T: PathGenerator<C>,
+        D: FnMut() -> u64,
+        G: FnMut(&Node<C>) -> bool,
+    {
+        let dense_grid = self.dense_grid;
+        self.search_with_index(from_struct, start, draw_tie_break, is_goal, |position| {
+            let (width, height) = dense_grid?;
+            (position.0 < width && position.1 < height).then(|| position.1 * width + position.0)
+        })
+    }
+
+    // positions index_of maps are closed in closed_slots instead of closed_nodes and closed_set
+    fn search_with_index<T, D, G, I>(
+        &mut self,
+        from_struct: &T,
+        start: (usize, usize),
+        mut draw_tie_break: D,
+        mut is_goal: G,
+        index_of: I,
+    ) -> Option<Rc<Node<C>>>
+    where
+        T: PathGenerator<C>,
+        D: FnMut() -> u64,
+        G: FnMut(&Node<C>) -> bool,
+        I: Fn((usize, usize)) -> Option<usize>,
+    {
+        // PathGenerator is used to build possible paths
+        let exposed_struct = from_struct;
+        let started = (self.clock)();
+        let start_heuristic = Self::initial_heuristic(exposed_struct, start, self.target);
+        // a lower bound of INFINITY proves the target can't be reached
+        if start_heuristic.is_infinite() {
+            return None;
+        }
+        self.trace_discovery(start);
+        self.que.push(Node::new(start, start_heuristic));
         loop {
-            if inst.que.is_empty() {
+            if self.que.is_empty() {
                 return None; // no elements left therefor no fast way out
             }
-            inst.que.sort();
-            let top = Rc::new(inst.que.remove(0));
+            if let Some(error) = self.interruption(started) {
+                self.error = Some(error);
+                return None;
+            }
+            #[cfg(feature = "profiling")]
+            let ordering_started = Instant::now();
+            self.que.sort();
+            let top = Rc::new(self.que.remove(0));
+            if let Some(budget) = self.pop_budget.as_mut() {
+                *budget -= 1;
+            }
+            #[cfg(feature = "profiling")]
+            let expansion_started = Instant::now();
+            #[cfg(feature = "profiling")]
+            {
+                self.time_in_ordering += expansion_started - ordering_started;
+            }
+            // duplicates of an already expanded position are left in the que, skip them here
+            if self.closed_node(top.position, &index_of).is_some() {
+                continue;
+            }
+            // the target is only accepted once popped, any cheaper route to it is popped first
+            if is_goal(&top) {
+                return Some(top);
+            }
+            self.expanded += 1;
+            self.trace_expansion(top.position);
             let possible_paths = exposed_struct.generate_paths(top.position);
             if !possible_paths.is_empty() {
                 for possible_path in possible_paths {
-                    if inst.pull_from_closed_by_position(possible_path).is_some() {
+                    self.trace_consideration(possible_path);
+                    let closed_cost = self
+                        .closed_node(possible_path, &index_of)
+                        .map(|closed_node| closed_node.cost);
+                    // a closed neighbour that can't be reopened gets neither cost calculated
+                    if closed_cost.is_some() && self.reopen_threshold.is_none() {
+                        continue;
+                    }
+                    let cost_started = self.edge_time_budget.map(|_| (self.clock)());
+                    let cost = exposed_struct.calculate_cost(top.position, possible_path);
+                    // a slow edge is dropped, the path may get longer or disappear but stays valid
+                    if let (Some(budget), Some(started)) = (self.edge_time_budget, cost_started) {
+                        if (self.clock)().duration_since(started) > budget {
+                            self.slow_edges += 1;
+                            continue;
+                        }
+                    }
+                    if cost.is_infinite() {
+                        continue;
+                    }
+                    let reached_cost = top.cost + cost + self.action_cost;
+                    if self.exceeds_seed(possible_path, reached_cost)
+                        || exposed_struct.prune(possible_path, reached_cost)
+                    {
                         continue;
                     }
-                    match inst.create_new_node(
-                        Rc::clone(&top),
-                        possible_path,
-                        exposed_struct.calculate_cost(top.position, possible_path),
-                        exposed_struct.calculate_heuristic_cost(possible_path, inst.target),
-                    ) {
-                        NextNodeResult::Ok(node) => inst.que.push(node),
-                        NextNodeResult::Finished => {
-                            let mut path = inst.reconstruct_path(Rc::clone(&top));
-                            path.insert(0, possible_path);
-                            return Some(path);
+                    if closed_cost
+                        .is_some_and(|closed_cost| !self.should_reopen(closed_cost, reached_cost))
+                    {
+                        continue;
+                    }
+                    let heuristic_cost =
+                        exposed_struct.calculate_heuristic_cost(possible_path, self.target);
+                    // like for the start, the target can't be reached from there
+                    if heuristic_cost.is_infinite() {
+                        continue;
+                    }
+                    if closed_cost.is_some() {
+                        match index_of(possible_path) {
+                            Some(index) => self.closed_slots[index] = None,
+                            None => {
+                                self.closed_nodes
+                                    .retain(|closed_node| closed_node.position != possible_path);
+                                self.closed_set.remove(possible_path);
+                            }
                         }
+                        self.reopened += 1;
                     }
+                    self.generated += 1;
+                    let mut node =
+                        self.create_new_node(Rc::clone(&top), possible_path, cost, heuristic_cost);
+                    node.drawn_tie_break = draw_tie_break();
+                    self.trace_discovery(possible_path);
+                    self.que.push(node);
+                }
+            }
+            if let Some(index) = index_of(top.position) {
+                self.closed_slots[index] = Some(top);
+            } else {
+                self.closed_set.insert(top.position);
+                self.closed_nodes.push(Rc::clone(&top));
+                if self
+                    .max_closed
+                    .is_some_and(|max_closed| self.closed_nodes.len() > max_closed)
+                {
+                    let forgotten = self.closed_nodes.remove(0);
+                    self.closed_set.remove(forgotten.position);
                 }
             }
-            inst.closed_nodes.push(Rc::clone(&top));
+            #[cfg(feature = "profiling")]
+            {
+                self.time_in_expansion += expansion_started.elapsed();
+            }
         }
     }
 
     fn create_new_node(
         &self,
-        old_node: Rc<Node>,
+        old_node: Rc<Node<C>>,
         new_position: (usize, usize),
-        cost: usize,
-        heuristic_cost: usize,
-    ) -> NextNodeResult<Node> {
-        if self.target_is_reached(&new_position) {
-            return NextNodeResult::Finished;
-        }
-        let new_cost = cost + old_node.cost;
-        NextNodeResult::Ok(Node {
+        cost: C,
+        heuristic_cost: C,
+    ) -> Node<C> {
+        let new_cost = cost + old_node.cost + self.action_cost;
+        Node {
             position: new_position,
             comes_from: Some(old_node),
             cost: new_cost,
             total_cost: heuristic_cost + new_cost,
-        })
+            tie_break: self.tie_break(&new_position),
+            drawn_tie_break: 0,
+        }
+    }
+
+    fn interruption(&self, started: Instant) -> Option<PathError> {
+        if self.pop_budget == Some(0) {
+            return Some(PathError::BudgetExhausted);
+        }
+        if self
+            .cancel_flag
+            .as_ref()
+            .is_some_and(|flag| flag.load(AtomicOrdering::Relaxed))
+        {
+            return Some(PathError::Cancelled);
+        }
+        match self.timeout {
+            Some(timeout) if (self.clock)().duration_since(started) >= timeout => {
+                Some(PathError::Timeout(timeout))
+            }
+            _ => None,
+        }
+    }
+
+    // the threshold is never 0, a cycle of zero cost edges gets back to its cells for the same
+    // cost and can't reopen them, so such cycles are walked around at most once
+    fn should_reopen(&self, closed_cost: C, new_cost: C) -> bool {
+        match self.reopen_threshold {
+            Some(threshold) => new_cost + threshold <= closed_cost,
+            None => false,
+        }
     }
 
-    fn target_is_reached(&self, position: &(usize, usize)) -> bool {
-        if self.target.0.is_some() && self.target.0.unwrap() != position.0 {
+    fn exceeds_seed(&self, position: (usize, usize), new_cost: C) -> bool {
+        match self.seed_costs.get(&position) {
+            Some(seed) => new_cost > *seed,
+            None => false,
+        }
+    }
+
+    fn tie_break(&self, position: &(usize, usize)) -> usize {
+        if self.preferred_positions.contains(position) {
+            return 0;
+        }
+        1
+    }
+
+    fn target_is_reached(
+        target: (Option<usize>, Option<usize>),
+        position: &(usize, usize),
+    ) -> bool {
+        if target.0.is_some() && target.0.unwrap() != position.0 {
             return false;
         }
-        if self.target.1.is_some() && self.target.1.unwrap() != position.1 {
+        if target.1.is_some() && target.1.unwrap() != position.1 {
             return false;
         }
         true
     }
 
-    fn reconstruct_path(&self, opt: Rc<Node>) -> Vec<(usize, usize)> {
-        let mut fastest_path = vec![opt.position];
-        let mut comes_from = opt.comes_from.as_ref();
-        loop {
-            if let Some(node) = comes_from {
-                fastest_path.push(node.position);
+    fn reconstruct_path(opt: Rc<Node<C>>) -> Vec<(usize, usize)> {
+        let mut fastest_path = Vec::new();
+        Self::reconstruct_into(&opt, |position| fastest_path.push(position));
+        Self::debug_assert_chain(&fastest_path, &opt);
+        fastest_path
+    }
+
+    // the path has one cell more than the goal node has links to its parents, checked in
+    // debug builds so a broken reconstruction fails loudly
+    fn debug_assert_chain(path: &[(usize, usize)], opt: &Rc<Node<C>>) {
+        if cfg!(debug_assertions) {
+            let mut links = 0;
+            let mut comes_from = opt.comes_from.as_ref();
+            while let Some(node) = comes_from {
+                links += 1;
                 comes_from = node.comes_from.as_ref();
-            } else {
-                return fastest_path;
             }
+            assert_eq!(
+                path.len(),
+                links + 1,
+                "reconstructed path of {} cells for a goal node with {} parent links",
+                path.len(),
+                links
+            );
         }
     }
 
-    fn pull_from_closed_by_position(&self, position: (usize, usize)) -> Option<&Rc<Node>> {
-        self.closed_nodes.iter().find(|closed_node| closed_node.position == position)
+    // hands the positions from the target back to the start to sink
+    fn reconstruct_into(opt: &Rc<Node<C>>, mut sink: impl FnMut((usize, usize))) {
+        sink(opt.position);
+        let mut comes_from = opt.comes_from.as_ref();
+        while let Some(node) = comes_from {
+            sink(node.position);
+            comes_from = node.comes_from.as_ref();
+        }
+    }
+
+    // None as soon as the path turns out to have more than max_len cells, before anything
+    // is allocated for it
+    fn reconstruct_path_capped(opt: Rc<Node<C>>, max_len: usize) -> Option<Vec<(usize, usize)>> {
+        let mut len = 1;
+        let mut comes_from = opt.comes_from.as_ref();
+        while let Some(node) = comes_from {
+            len += 1;
+            if len > max_len {
+                return None;
+            }
+            comes_from = node.comes_from.as_ref();
+        }
+        if len > max_len {
+            return None;
+        }
+        let mut path = Vec::with_capacity(len);
+        Self::reconstruct_into(&opt, |position| path.push(position));
+        Self::debug_assert_chain(&path, &opt);
+        Some(path)
+    }
+
+    fn reconstruct_step_costs(opt: Rc<Node<C>>) -> Vec<((usize, usize), C)> {
+        let mut steps = vec![(opt.position, opt.cost)];
+        let mut comes_from = opt.comes_from.as_ref();
+        while let Some(node) = comes_from {
+            steps.push((node.position, node.cost));
+            comes_from = node.comes_from.as_ref();
+        }
+        steps
+    }
+
+    fn closed_node(
+        &self,
+        position: (usize, usize),
+        index_of: impl Fn((usize, usize)) -> Option<usize>,
+    ) -> Option<&Rc<Node<C>>> {
+        match index_of(position) {
+            Some(index) => self.closed_slots[index].as_ref(),
+            None => self.pull_from_closed_by_position(position),
+        }
+    }
+
+    fn pull_from_closed_by_position(&self, position: (usize, usize)) -> Option<&Rc<Node<C>>> {
+        if !self.closed_set.contains(position) {
+            return None;
+        }
+        self.closed_nodes
+            .iter()
+            .find(|closed_node| closed_node.position == position)
     }
 }
 
 #[derive(Eq, Debug)]
-struct Node {
+struct Node<C: Cost> {
     position: (usize, usize),
-    cost: usize,
-    total_cost: usize,
-    comes_from: Option<Rc<Node>>,
+    cost: C,
+    total_cost: C,
+    tie_break: usize,
+    drawn_tie_break: u64,
+    comes_from: Option<Rc<Node<C>>>,
 }
 
-impl Node {
-    fn new(position: (usize, usize), total_cost: usize) -> Self {
+impl<C: Cost> Node<C> {
+    fn new(position: (usize, usize), total_cost: C) -> Self {
         Self {
             position,
             comes_from: None,
-            cost: 0,
+            cost: C::default(),
             total_cost,
+            tie_break: 0,
+            drawn_tie_break: 0,
         }
     }
 }
 
-impl PartialEq for Node {
+impl<C: Cost> PartialEq for Node<C> {
     fn eq(&self, other: &Self) -> bool {
-        self.total_cost == other.total_cost
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-impl Ord for Node {
+impl<C: Cost> Ord for Node<C> {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.total_cost.cmp(&other.total_cost)
+        self.total_cost
+            .cmp(&other.total_cost)
+            .then(self.tie_break.cmp(&other.tie_break))
+            .then(self.drawn_tie_break.cmp(&other.drawn_tie_break))
     }
 }
 
-impl PartialOrd for Node {
+impl<C: Cost> PartialOrd for Node<C> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
     }
-
-    fn ge(&self, other: &Self) -> bool {
-        self.total_cost >= other.total_cost
-    }
-    fn le(&self, other: &Self) -> bool {
-        self.total_cost <= other.total_cost
-    }
-    fn gt(&self, other: &Self) -> bool {
-        self.total_cost > other.total_cost
-    }
-    fn lt(&self, other: &Self) -> bool {
-        self.total_cost < other.total_cost
-    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    #[test]
-    fn testrun() {
-        fn calc_usize_diff(x: usize, y: usize) -> usize {
-            if x > y {
-                return x - y;
-            }
-            y - x
-        }
+    use std::cell::Cell;
+    use std::collections::HashMap;
 
-        struct Map {
-            blocks: Vec<(usize, usize)>,
+    fn calc_usize_diff(x: usize, y: usize) -> usize {
+        if x > y {
+            return x - y;
         }
-        impl Map {
-            fn path_is_possible(&self, possible_path: (usize, usize)) -> Option<(usize, usize)> {
-                if self.blocks.contains(&possible_path) {
-                    return None;
-                }
-                Some(possible_path)
+        y - x
+    }
+
+    struct Map {
+        blocks: Vec<(usize, usize)>,
+    }
+    impl Map {
+        fn path_is_possible(&self, possible_path: (usize, usize)) -> Option<(usize, usize)> {
+            if self.blocks.contains(&possible_path) {
+                return None;
             }
+            Some(possible_path)
         }
-        impl PathGenerator for Map {
-            fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
-                let mut possible_paths: Vec<(usize, usize)> = Vec::new();
-
-                if from_position.0 != 0 && from_position.1 != 0 {
-                    for possible_path in [
-                        (from_position.0 - 1, from_position.1 - 1),
-                        (from_position.0, from_position.1 - 1),
-                        (from_position.0 - 1, from_position.1),
-                    ] {
-                        if let Some(path_) = self.path_is_possible(possible_path) {
-                            possible_paths.push(path_)
-                        }
-                    }
-                };
+    }
+    impl PathGenerator for Map {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            let mut possible_paths: Vec<(usize, usize)> = Vec::new();
+
+            if from_position.0 != 0 && from_position.1 != 0 {
                 for possible_path in [
-                    (from_position.0 + 1, from_position.1 + 1),
-                    (from_position.0, from_position.1 + 1),
-                    (from_position.0 + 1, from_position.1),
+                    (from_position.0 - 1, from_position.1 - 1),
+                    (from_position.0, from_position.1 - 1),
+                    (from_position.0 - 1, from_position.1),
                 ] {
                     if let Some(path_) = self.path_is_possible(possible_path) {
                         possible_paths.push(path_)
                     }
                 }
-                possible_paths
+            };
+            for possible_path in [
+                (from_position.0 + 1, from_position.1 + 1),
+                (from_position.0, from_position.1 + 1),
+                (from_position.0 + 1, from_position.1),
+            ] {
+                if let Some(path_) = self.path_is_possible(possible_path) {
+                    possible_paths.push(path_)
+                }
             }
-            #[allow(unused_variables)]
-            fn calculate_cost(
-                &self,
-                current_position: (usize, usize),
-                next_position: (usize, usize),
-            ) -> usize {
-                1
+            possible_paths
+        }
+        #[allow(unused_variables)]
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            1
+        }
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            if target.0.is_none() && target.1.is_none() {
+                return 0;
             }
-            fn calculate_heuristic_cost(
-                &self,
-                position: (usize, usize),
-                target: (Option<usize>, Option<usize>),
-            ) -> usize {
-                if target.0.is_none() && target.1.is_none() {
-                    return 0;
-                }
-                if target.0.is_none() {
-                    return calc_usize_diff(target.1.unwrap(), position.1);
-                }
-                if target.1.is_none() {
-                    return calc_usize_diff(target.0.unwrap(), position.0);
+            if target.0.is_none() {
+                return calc_usize_diff(target.1.unwrap(), position.1);
+            }
+            if target.1.is_none() {
+                return calc_usize_diff(target.0.unwrap(), position.0);
+            }
+            f64::sqrt(
+                ((calc_usize_diff(target.0.unwrap(), position.0) ^ 2)
+                    + (calc_usize_diff(target.1.unwrap(), position.1) ^ 2)) as f64,
+            ) as usize
+        }
+    }
+
+    // explicit directed graph over (id, 0) positions with a per-node heuristic
+    pub(crate) struct Graph {
+        pub(crate) edges: Vec<(usize, usize, usize)>,
+        pub(crate) heuristic: HashMap<usize, usize>,
+    }
+
+    impl Graph {
+        pub(crate) fn new(edges: Vec<(usize, usize, usize)>) -> Self {
+            Self {
+                edges,
+                heuristic: HashMap::new(),
+            }
+        }
+    }
+
+    impl PathGenerator for Graph {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.edges
+                .iter()
+                .filter(|edge| edge.0 == from_position.0)
+                .map(|edge| (edge.1, 0))
+                .collect()
+        }
+
+        fn reverse_neighbors(&self, to_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.edges
+                .iter()
+                .filter(|edge| edge.1 == to_position.0)
+                .map(|edge| (edge.0, 0))
+                .collect()
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            self.edges
+                .iter()
+                .find(|edge| edge.0 == current_position.0 && edge.1 == next_position.0)
+                .expect("cost requested for an edge missing from the graph")
+                .2
+        }
+
+        #[allow(unused_variables)]
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            *self.heuristic.get(&position.0).unwrap_or(&0)
+        }
+    }
+
+    // 4-connected bounded grid where entering a cell costs its weight (default 1)
+    struct WeightedMap {
+        width: usize,
+        height: usize,
+        blocks: Vec<(usize, usize)>,
+        weights: HashMap<(usize, usize), usize>,
+    }
+    impl WeightedMap {
+        fn new(width: usize, height: usize) -> Self {
+            Self {
+                width,
+                height,
+                blocks: Vec::new(),
+                weights: HashMap::new(),
+            }
+        }
+    }
+    impl PathGenerator for WeightedMap {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            let (x, y) = from_position;
+            let mut possible_paths = Vec::new();
+            if x > 0 {
+                possible_paths.push((x - 1, y));
+            }
+            if y > 0 {
+                possible_paths.push((x, y - 1));
+            }
+            if x + 1 < self.width {
+                possible_paths.push((x + 1, y));
+            }
+            if y + 1 < self.height {
+                possible_paths.push((x, y + 1));
+            }
+            possible_paths.retain(|position| !self.blocks.contains(position));
+            possible_paths
+        }
+        #[allow(unused_variables)]
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            *self.weights.get(&next_position).unwrap_or(&1)
+        }
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            target.0.map_or(0, |x| calc_usize_diff(x, position.0))
+                + target.1.map_or(0, |y| calc_usize_diff(y, position.1))
+        }
+    }
+
+    // checks path, ordered like in run, against a plain Dijkstra from start that shares no code
+    // with the searches
+    pub(crate) fn assert_optimal<T: PathGenerator>(
+        from_struct: &T,
+        start: (usize, usize),
+        goal: (usize, usize),
+        path: &[(usize, usize)],
+    ) {
+        assert_eq!(
+            path.first(),
+            Some(&goal),
+            "the path doesn't end at the goal"
+        );
+        assert_eq!(
+            path.last(),
+            Some(&start),
+            "the path doesn't begin at the start"
+        );
+        let mut cost = 0;
+        for pair in path.windows(2) {
+            assert!(
+                from_struct.generate_paths(pair[1]).contains(&pair[0]),
+                "{:?} -> {:?} is not an edge",
+                pair[1],
+                pair[0]
+            );
+            cost += from_struct.calculate_cost(pair[1], pair[0]);
+        }
+        let mut settled = HashMap::new();
+        let mut que = std::collections::BinaryHeap::new();
+        que.push(std::cmp::Reverse((0, start)));
+        while let Some(std::cmp::Reverse((reached, position))) = que.pop() {
+            if settled.contains_key(&position) {
+                continue;
+            }
+            settled.insert(position, reached);
+            if position == goal {
+                break;
+            }
+            for next in from_struct.generate_paths(position) {
+                if !settled.contains_key(&next) {
+                    let next_cost = reached + from_struct.calculate_cost(position, next);
+                    que.push(std::cmp::Reverse((next_cost, next)));
                 }
-                f64::sqrt(
-                    ((calc_usize_diff(target.0.unwrap(), position.0) ^ 2)
-                        + (calc_usize_diff(target.1.unwrap(), position.1) ^ 2))
-                        as f64,
-                ) as usize
             }
         }
+        assert_eq!(
+            Some(&cost),
+            settled.get(&goal),
+            "the path is not the cheapest"
+        );
+    }
 
+    #[test]
+    fn testrun() {
         let map_fixture = Map {
             blocks: vec![(2, 2)],
         };
         let path = AStar::run(&map_fixture, (0, 0), (Some(3), Some(3)));
         assert_eq!(path.unwrap(), vec![(3, 3), (2, 3), (1, 2), (1, 1), (0, 0)])
     }
+
+    #[test]
+    fn test_step_costs() {
+        let mut map_fixture = WeightedMap::new(4, 1);
+        map_fixture.weights.insert((1, 0), 3);
+        map_fixture.weights.insert((2, 0), 2);
+        let steps = AStar::run_with_step_costs(&map_fixture, (0, 0), (Some(3), Some(0))).unwrap();
+        assert_eq!(
+            steps,
+            vec![((0, 0), 0), ((1, 0), 3), ((2, 0), 5), ((3, 0), 6)]
+        );
+        assert!(steps.windows(2).all(|pair| pair[0].1 < pair[1].1));
+        let path = AStar::run(&map_fixture, (0, 0), (Some(3), Some(0))).unwrap();
+        assert_optimal(&map_fixture, (0, 0), (3, 0), &path);
+        assert_eq!(
+            steps.iter().rev().map(|step| step.0).collect::<Vec<_>>(),
+            path
+        );
+
+        // the direct edge is generated first but the detour is cheaper
+        let graph = Graph::new(vec![(0, 4, 10), (0, 1, 1), (1, 2, 1), (2, 3, 1), (3, 4, 1)]);
+        let steps = AStar::run_with_step_costs(&graph, (0, 0), (Some(4), Some(0))).unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                ((0, 0), 0),
+                ((1, 0), 1),
+                ((2, 0), 2),
+                ((3, 0), 3),
+                ((4, 0), 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_goal_is_accepted_when_popped() {
+        // the goal is generated right away over the edge costing 10, accepting it then instead
+        // of when it's popped returns that edge
+        let graph = Graph::new(vec![(0, 3, 10), (0, 1, 1), (1, 2, 1), (2, 3, 1)]);
+        let path = AStar::run(&graph, (0, 0), (Some(3), Some(0))).unwrap();
+        assert_optimal(&graph, (0, 0), (3, 0), &path);
+
+        let mut map = WeightedMap::new(8, 8);
+        for x in 0..8 {
+            for y in 0..8 {
+                map.weights.insert((x, y), 1 + (x * 7 + y * 13) % 5);
+            }
+        }
+        map.blocks.extend([(3, 3), (3, 4), (4, 3), (5, 1)]);
+        for (start, goal) in [((0, 0), (7, 7)), ((7, 0), (0, 7)), ((2, 5), (6, 2))] {
+            let path = AStar::run(&map, start, (Some(goal.0), Some(goal.1))).unwrap();
+            assert_optimal(&map, start, goal, &path);
+        }
+    }
+
+    #[test]
+    fn test_coherent() {
+        let mut map_fixture = WeightedMap::new(3, 3);
+        map_fixture.blocks.push((1, 1));
+        let target = (Some(2), Some(2));
+        for previous_path in [
+            vec![(2, 2), (2, 1), (2, 0), (1, 0), (0, 0)],
+            vec![(2, 2), (1, 2), (0, 2), (0, 1), (0, 0)],
+        ] {
+            let path = AStar::run_coherent(&map_fixture, (0, 0), target, &previous_path);
+            assert_eq!(path.unwrap(), previous_path);
+        }
+    }
+
+    #[test]
+    fn test_seed_costs() {
+        // 1 has an inflated heuristic, so 3 is first reached through the expensive 2
+        let mut graph = Graph::new(vec![
+            (0, 1, 1),
+            (0, 2, 4),
+            (1, 3, 1),
+            (2, 3, 1),
+            (3, 4, 3),
+            (4, 5, 1),
+        ]);
+        graph.heuristic.insert(1, 5);
+        let target = (Some(5), Some(0));
+        let optimal = vec![(5, 0), (4, 0), (3, 0), (1, 0), (0, 0)];
+
+        let (path, stats) = AStar::builder().run_with_stats(&graph, (0, 0), target);
+        assert_eq!(path.unwrap(), optimal);
+
+        let seed = HashMap::from([((3, 0), 2)]);
+        let path = AStar::run_with_seed_costs(&graph, (0, 0), target, &seed);
+        assert_eq!(path.unwrap(), optimal);
+        let mut inst = AStar::new(target);
+        inst.seed_costs = seed;
+        inst.search(&graph, (0, 0));
+        assert!(inst.expanded < stats.expanded);
+    }
+
+    struct DeadEndPruned(GridMap);
+
+    impl PathGenerator for DeadEndPruned {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.0.generate_paths(from_position)
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            self.0.calculate_cost(current_position, next_position)
+        }
+
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            self.0.calculate_heuristic_cost(position, target)
+        }
+
+        #[allow(unused_variables)]
+        fn prune(&self, position: (usize, usize), g_cost: usize) -> bool {
+            position.0 < 4 && position.1 < 3
+        }
+    }
+
+    #[test]
+    fn test_run_into() {
+        let map = GridMap::builder(6, 4)
+            .block_all([(3, 0), (3, 1), (3, 2)])
+            .build();
+        let target = (Some(5), Some(0));
+        let mut buffer = Vec::with_capacity(32);
+        assert!(AStar::run_into(&map, (0, 0), target, |position| buffer.push(position)));
+        assert_eq!(Some(buffer.clone()), AStar::run(&map, (0, 0), target));
+
+        buffer.clear();
+        let unreachable = (Some(9), Some(0));
+        assert!(!AStar::run_into(&map, (0, 0), unreachable, |position| {
+            buffer.push(position)
+        }));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_prune() {
+        // the pocket above row 3 is closed toward the target, only row 3 leads around the wall
+        let map = GridMap::builder(7, 4)
+            .block_all([(4, 0), (4, 1), (4, 2)])
+            .build();
+        let target = (Some(6), Some(0));
+        let (path, stats) = AStar::run_with_stats(&map, (0, 3), target);
+        let pruned = DeadEndPruned(map);
+        let (pruned_path, pruned_stats) = AStar::run_with_stats(&pruned, (0, 3), target);
+        assert_eq!(pruned_path, path);
+        assert!(pruned_stats.expanded < stats.expanded);
+    }
+
+    // counts neighbours handed out and cost calls
+    struct CountedCalls {
+        map: GridMap,
+        neighbours: Cell<usize>,
+        costs: Cell<usize>,
+        heuristics: Cell<usize>,
+    }
+
+    impl PathGenerator for CountedCalls {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            let possible_paths = self.map.generate_paths(from_position);
+            self.neighbours
+                .set(self.neighbours.get() + possible_paths.len());
+            possible_paths
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            self.costs.set(self.costs.get() + 1);
+            self.map.calculate_cost(current_position, next_position)
+        }
+
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            self.heuristics.set(self.heuristics.get() + 1);
+            self.map.calculate_heuristic_cost(position, target)
+        }
+    }
+
+    #[test]
+    fn test_closed_neighbours_are_not_costed() {
+        let map = CountedCalls {
+            map: GridMap::builder(10, 10)
+                .block_all((1..9).map(|y| (5, y)))
+                .build(),
+            neighbours: Cell::new(0),
+            costs: Cell::new(0),
+            heuristics: Cell::new(0),
+        };
+        let (path, stats) = AStar::run_with_stats(&map, (0, 5), (Some(9), Some(5)));
+        assert!(path.is_some());
+        // start plus every queued node, nothing for neighbours that were already closed
+        assert_eq!(map.heuristics.get(), stats.generated + 1);
+        assert_eq!(map.costs.get(), stats.generated);
+        assert!(map.costs.get() * 3 < map.neighbours.get() * 2);
+    }
+
+    #[test]
+    fn test_path_matches_parent_chain() {
+        let map = GridMap::new(6, 4);
+        let mut inst = AStar::new((Some(5), Some(3)));
+        let goal = inst.search(&map, (0, 0)).unwrap();
+        let path = AStar::reconstruct_path(Rc::clone(&goal));
+        assert_eq!(path.len(), 9);
+        AStar::debug_assert_chain(&path, &goal);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "reconstructed path of 8 cells for a goal node with 8 parent links")]
+    fn test_broken_chain_panics() {
+        let map = GridMap::new(6, 4);
+        let mut inst = AStar::new((Some(5), Some(3)));
+        let goal = inst.search(&map, (0, 0)).unwrap();
+        let path = AStar::reconstruct_path(Rc::clone(&goal));
+        AStar::debug_assert_chain(&path[1..], &goal);
+    }
+
+    #[test]
+    fn test_run_to_row_and_column() {
+        // row 6 can only be reached over (9, 5) or over (1, 5), which is closer to the start
+        // but costs 20 to enter
+        let mut map = WeightedMap::new(10, 8);
+        map.blocks
+            .extend((0..10).filter(|x| *x != 9 && *x != 1).map(|x| (x, 5)));
+        map.weights.insert((1, 5), 20);
+        let path = AStar::run_to_row(&map, (4, 0), 6).unwrap();
+        assert_eq!(path[0].1, 6);
+        assert_optimal(&map, (4, 0), (9, 6), &path);
+        assert_eq!(path[0], (9, 6));
+        let cost: usize = path
+            .windows(2)
+            .map(|pair| map.calculate_cost(pair[1], pair[0]))
+            .sum();
+        assert_eq!(cost, 11);
+
+        let path = AStar::run_to_column(&map, (4, 0), 0).unwrap();
+        assert_eq!(path[0], (0, 0));
+        assert_eq!(path.len(), 5);
+        assert!(AStar::run_to_row(&map, (4, 0), 8).is_none());
+    }
+
+    #[test]
+    fn test_initial_heuristic() {
+        let map = GridMap::builder(8, 6).block((3, 2)).build();
+        let target = (Some(7), Some(5));
+        let mut inst = AStar::new(target);
+        let mut node = inst.search(&map, (1, 2)).unwrap();
+        while let Some(parent) = node.comes_from.clone() {
+            node = parent;
+        }
+        assert_eq!(node.position, (1, 2));
+        assert_eq!(
+            AStar::initial_heuristic(&map, (1, 2), target),
+            node.total_cost
+        );
+        assert_eq!(AStar::initial_heuristic(&map, (1, 2), target), 9);
+    }
+
+    // the walled in corner is known to be unreachable, its heuristic says so
+    struct WalledCorner(GridMap, Cell<usize>);
+
+    impl PathGenerator for WalledCorner {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.1.set(self.1.get() + 1);
+            self.0.generate_paths(from_position)
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            self.0.calculate_cost(current_position, next_position)
+        }
+
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            if target == (Some(7), Some(7)) {
+                return usize::MAX;
+            }
+            self.0.calculate_heuristic_cost(position, target)
+        }
+    }
+
+    // (0, 2) is a dead end whose heuristic says so
+    struct DeadEnd(GridMap);
+
+    impl PathGenerator for DeadEnd {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.0.generate_paths(from_position)
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            self.0.calculate_cost(current_position, next_position)
+        }
+
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            if position == (0, 2) {
+                return usize::MAX;
+            }
+            self.0.calculate_heuristic_cost(position, target)
+        }
+    }
+
+    #[test]
+    fn test_infinite_heuristic_is_not_queued() {
+        let map = DeadEnd(GridMap::builder(3, 3).block((1, 2)).build());
+        let mut inst = AStar::new((Some(2), Some(1)));
+        inst.trace = Some(SearchTrace::default());
+        let goal = inst.search(&map, (0, 1)).unwrap();
+        assert_eq!(AStar::reconstruct_path(goal).len(), 3);
+        assert!(!inst.trace.unwrap().discovery_order.contains(&(0, 2)));
+        assert!(AStar::run(&map, (0, 2), (Some(2), Some(1))).is_none());
+    }
+
+    #[test]
+    fn test_infinite_start_heuristic() {
+        let map = WalledCorner(
+            GridMap::builder(8, 8).block_all([(6, 7), (7, 6)]).build(),
+            Cell::new(0),
+        );
+        let (path, stats) = AStar::run_with_stats(&map, (0, 0), (Some(7), Some(7)));
+        assert!(path.is_none());
+        assert_eq!((stats.expanded, map.1.get()), (0, 0));
+        assert!(AStar::run(&map, (0, 0), (Some(6), Some(6))).is_some());
+    }
+
+    #[test]
+    fn test_shared_budget_counter() {
+        let map = GridMap::builder(10, 10)
+            .block_all((0..9).map(|y| (5, y)))
+            .build();
+        let target = (Some(9), Some(0));
+        let (_, stats) = AStar::run_with_stats(&map, (0, 0), target);
+        let mut budget = 60;
+
+        let path = AStar::run_with_budget_counter(&map, (9, 9), target, &mut budget);
+        assert_eq!(path.map(|path| path.len()), Ok(10));
+        let first = 60 - budget;
+        assert!(first >= 10 && first < stats.expanded);
+
+        assert_eq!(
+            AStar::run_with_budget_counter(&map, (0, 0), target, &mut budget),
+            Err(PathError::BudgetExhausted)
+        );
+        assert_eq!(budget, 0);
+        assert!(first + stats.expanded > 60);
+        assert_eq!(
+            AStar::run_with_budget_counter(&map, (9, 9), target, &mut budget),
+            Err(PathError::BudgetExhausted)
+        );
+    }
 }
@@ -0,0 +1,116 @@
+use crate::{AStar, Cost, PathGenerator};
+
+// cells of the Bresenham line from `from` to `to`, both included, each one a king move apart
+pub(crate) fn line_cells(from: (usize, usize), to: (usize, usize)) -> Vec<(usize, usize)> {
+    let (mut x, mut y) = (from.0 as i64, from.1 as i64);
+    let (dx, dy) = (to.0 as i64 - x, to.1 as i64 - y);
+    let (step_x, step_y) = (dx.signum(), dy.signum());
+    let (dx, dy) = (dx.abs(), -dy.abs());
+    let mut error = dx + dy;
+    let mut cells = vec![from];
+    while (x, y) != (to.0 as i64, to.1 as i64) {
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+        cells.push((x as usize, y as usize));
+    }
+    cells
+}
+
+impl<C: Cost> AStar<C> {
+    // run toward a concrete goal, skipping the search when the generator reports line of sight
+    pub fn run_to<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        goal: (usize, usize),
+    ) -> Option<Vec<(usize, usize)>> {
+        if from_struct.line_of_sight(start, goal) {
+            let mut path = line_cells(start, goal);
+            path.reverse();
+            return Some(path);
+        }
+        Self::run(from_struct, start, (Some(goal.0), Some(goal.1)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GridMap;
+    use std::cell::Cell;
+
+    // counts generate_paths calls to tell the search from the fast path
+    struct Counted {
+        map: GridMap,
+        calls: Cell<usize>,
+    }
+
+    impl PathGenerator for Counted {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.calls.set(self.calls.get() + 1);
+            self.map.generate_paths(from_position)
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            self.map.calculate_cost(current_position, next_position)
+        }
+
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            self.map.calculate_heuristic_cost(position, target)
+        }
+
+        fn line_of_sight(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+            self.map.line_of_sight(from, to)
+        }
+    }
+
+    #[test]
+    fn test_line_cells() {
+        assert_eq!(
+            line_cells((0, 0), (3, 1)),
+            vec![(0, 0), (1, 0), (2, 1), (3, 1)]
+        );
+        assert_eq!(line_cells((2, 2), (2, 0)), vec![(2, 2), (2, 1), (2, 0)]);
+        assert_eq!(line_cells((1, 1), (1, 1)), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn test_line_of_sight_fast_path() {
+        let counted = Counted {
+            map: GridMap::builder(8, 8).diagonal(true).build(),
+            calls: Cell::new(0),
+        };
+        let path = AStar::run_to(&counted, (1, 1), (6, 3)).unwrap();
+        assert_eq!(counted.calls.get(), 0);
+        assert_eq!(path, vec![(6, 3), (5, 3), (4, 2), (3, 2), (2, 1), (1, 1)]);
+        assert_eq!(
+            path.len(),
+            AStar::run(&counted.map, (1, 1), (Some(6), Some(3)))
+                .unwrap()
+                .len()
+        );
+
+        // a wall on the line falls back to the search
+        let counted = Counted {
+            map: GridMap::builder(8, 8).diagonal(true).block((4, 2)).build(),
+            calls: Cell::new(0),
+        };
+        let path = AStar::run_to(&counted, (1, 1), (6, 3)).unwrap();
+        assert!(counted.calls.get() > 0);
+        assert!(!path.contains(&(4, 2)));
+    }
+}
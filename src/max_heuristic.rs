@@ -0,0 +1,125 @@
+use crate::{Cost, PathGenerator};
+
+type Heuristic<'a, C> = Box<dyn Fn((usize, usize), (Option<usize>, Option<usize>)) -> C + 'a>;
+
+// estimates with the largest of the heuristic of inner and every added one, the result is
+// only admissible when all of them are
+pub struct MaxHeuristic<'a, G, C = usize> {
+    inner: G,
+    heuristics: Vec<Heuristic<'a, C>>,
+}
+
+impl<'a, G, C: Cost> MaxHeuristic<'a, G, C> {
+    pub fn new(inner: G) -> Self {
+        Self {
+            inner,
+            heuristics: Vec::new(),
+        }
+    }
+
+    pub fn with(
+        mut self,
+        heuristic: impl Fn((usize, usize), (Option<usize>, Option<usize>)) -> C + 'a,
+    ) -> Self {
+        self.heuristics.push(Box::new(heuristic));
+        self
+    }
+}
+
+impl<G: PathGenerator<C>, C: Cost> PathGenerator<C> for MaxHeuristic<'_, G, C> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner.generate_paths(from_position)
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.inner.calculate_cost(current_position, next_position)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        self.heuristics
+            .iter()
+            .map(|heuristic| heuristic(position, target))
+            .fold(
+                self.inner.calculate_heuristic_cost(position, target),
+                Ord::max,
+            )
+    }
+
+    fn reverse_neighbors(&self, to_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner.reverse_neighbors(to_position)
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap};
+
+    fn dx(position: (usize, usize), target: (Option<usize>, Option<usize>)) -> usize {
+        target.0.map_or(0, |x| x.abs_diff(position.0))
+    }
+
+    fn dy(position: (usize, usize), target: (Option<usize>, Option<usize>)) -> usize {
+        target.1.map_or(0, |y| y.abs_diff(position.1))
+    }
+
+    // GridMap without its heuristic, so every estimate comes from the added ones
+    struct Flat(GridMap);
+
+    impl PathGenerator for Flat {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.0.generate_paths(from_position)
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            self.0.calculate_cost(current_position, next_position)
+        }
+
+        #[allow(unused_variables)]
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            0
+        }
+    }
+
+    fn flat() -> Flat {
+        Flat(
+            GridMap::builder(12, 12)
+                .block_all([(5, 4), (5, 5), (5, 6)])
+                .build(),
+        )
+    }
+
+    #[test]
+    fn test_max_expands_least() {
+        let (start, target) = ((1, 2), (Some(10), Some(8)));
+        let (optimal, _) = AStar::run_with_stats(&flat(), start, target);
+        let x_only = MaxHeuristic::new(flat()).with(dx);
+        let (x_path, x_stats) = AStar::run_with_stats(&x_only, start, target);
+        let y_only = MaxHeuristic::new(flat()).with(dy);
+        let (y_path, y_stats) = AStar::run_with_stats(&y_only, start, target);
+        let both = MaxHeuristic::new(flat()).with(dx).with(dy);
+        let (path, stats) = AStar::run_with_stats(&both, start, target);
+        for other in [&optimal, &x_path, &y_path] {
+            assert_eq!(path.as_ref().map(Vec::len), other.as_ref().map(Vec::len));
+        }
+        assert!(stats.expanded <= x_stats.expanded);
+        assert!(stats.expanded <= y_stats.expanded);
+        assert_eq!(both.calculate_heuristic_cost(start, target), 9);
+    }
+}
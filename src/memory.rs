@@ -0,0 +1,29 @@
+use crate::Node;
+use std::mem::size_of;
+use std::rc::Rc;
+
+// rough upper bound in bytes for a search with the default usize cost that settles
+// expected_nodes nodes: each one counted once in the open list, once behind an Rc on the
+// closed list and once in the closed set, which spends a control byte per entry.
+// Spare capacity of the collections is not included
+pub fn estimated_memory(expected_nodes: usize) -> usize {
+    let open = size_of::<Node<usize>>();
+    // an Rc allocation carries its strong and weak counts next to the node
+    let closed = size_of::<Rc<Node<usize>>>() + 2 * size_of::<usize>() + size_of::<Node<usize>>();
+    let closed_set = size_of::<(usize, usize)>() + 1;
+    expected_nodes * (open + closed + closed_set)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_estimated_memory() {
+        assert_eq!(estimated_memory(0), 0);
+        let per_node = estimated_memory(1);
+        assert!(per_node > size_of::<Node<usize>>());
+        assert_eq!(estimated_memory(1000), 1000 * per_node);
+        assert_eq!(estimated_memory(2000), 2 * estimated_memory(1000));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{AStar, Cost, PathGenerator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Walk,
+    Swim,
+}
+
+impl Mode {
+    fn other(self) -> Self {
+        match self {
+            Mode::Walk => Mode::Swim,
+            Mode::Swim => Mode::Walk,
+        }
+    }
+}
+
+// a state is packed into a position as (x * 2 + mode, y) like in FuelMap
+fn pack(position: (usize, usize), mode: Mode) -> (usize, usize) {
+    let x = position
+        .0
+        .checked_mul(2)
+        .unwrap_or_else(|| panic!("x = {} doesn't fit a MultiModeMap state", position.0));
+    (x + (mode == Mode::Swim) as usize, position.1)
+}
+
+fn unpack(state: (usize, usize)) -> ((usize, usize), Mode) {
+    let mode = if state.0 % 2 == 1 {
+        Mode::Swim
+    } else {
+        Mode::Walk
+    };
+    ((state.0 / 2, state.1), mode)
+}
+
+// searches over (position, mode) states, a move keeps the mode and needs passable(next, mode),
+// switching the mode stays on the cell, costs switch_cost and needs passable(cell, new mode)
+pub struct MultiModeMap<'a, T, F, C = usize> {
+    inner: &'a T,
+    passable: F,
+    switch_cost: C,
+}
+
+impl<'a, T, F: Fn((usize, usize), Mode) -> bool, C: Cost> MultiModeMap<'a, T, F, C> {
+    pub fn new(inner: &'a T, passable: F, switch_cost: C) -> Self {
+        Self {
+            inner,
+            passable,
+            switch_cost,
+        }
+    }
+
+    // ordered like in AStar::run, a switch shows up as the cell twice, once in each mode
+    pub fn run(
+        &self,
+        start: (usize, usize),
+        start_mode: Mode,
+        target: (Option<usize>, Option<usize>),
+    ) -> Option<Vec<((usize, usize), Mode)>>
+    where
+        T: PathGenerator<C>,
+    {
+        let mut inst = AStar::new(target);
+        let goal = inst.search_with(
+            &ModeStates { map: self },
+            pack(start, start_mode),
+            || 0,
+            |node| AStar::<C>::target_is_reached(target, &unpack(node.position).0),
+        )?;
+        let path = AStar::reconstruct_path(goal);
+        Some(path.into_iter().map(unpack).collect())
+    }
+}
+
+// the packed states as positions, only seen by the search in MultiModeMap::run
+struct ModeStates<'b, 'a, T, F, C> {
+    map: &'b MultiModeMap<'a, T, F, C>,
+}
+
+impl<T, F, C> PathGenerator<C> for ModeStates<'_, '_, T, F, C>
+where
+    T: PathGenerator<C>,
+    F: Fn((usize, usize), Mode) -> bool,
+    C: Cost,
+{
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let (position, mode) = unpack(from_position);
+        let mut possible_paths: Vec<(usize, usize)> = self
+            .map
+            .inner
+            .generate_paths(position)
+            .into_iter()
+            .filter(|next| (self.map.passable)(*next, mode))
+            .map(|next| pack(next, mode))
+            .collect();
+        if (self.map.passable)(position, mode.other()) {
+            possible_paths.push(pack(position, mode.other()));
+        }
+        possible_paths
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        let (current, _) = unpack(current_position);
+        let (next, _) = unpack(next_position);
+        if current == next {
+            return self.map.switch_cost;
+        }
+        self.map.inner.calculate_cost(current, next)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        self.map
+            .inner
+            .calculate_heuristic_cost(unpack(position).0, target)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GridMap;
+
+    // water on column 4 apart from the bottom row, the beaches on columns 3 and 5 can be
+    // walked and swum
+    fn passable(position: (usize, usize), mode: Mode) -> bool {
+        let water = position.0 == 4 && position.1 < 4;
+        let beach = position.0 == 3 || position.0 == 5;
+        match mode {
+            Mode::Walk => !water,
+            Mode::Swim => water || beach,
+        }
+    }
+
+    #[test]
+    fn test_switch_cost_decides_crossing() {
+        let map = GridMap::new(9, 5);
+        let target = (Some(8), Some(0));
+
+        // 8 steps and two switches for 2 against 16 steps around the water
+        let cheap = MultiModeMap::new(&map, passable, 2);
+        let path = cheap.run((0, 0), Mode::Walk, target).unwrap();
+        assert!(path.contains(&((4, 0), Mode::Swim)));
+        assert_eq!(path.len(), 11);
+        assert_eq!(path.first(), Some(&((8, 0), Mode::Walk)));
+        assert_eq!(path.last(), Some(&((0, 0), Mode::Walk)));
+
+        let expensive = MultiModeMap::new(&map, passable, 5);
+        let path = expensive.run((0, 0), Mode::Walk, target).unwrap();
+        assert!(path.iter().all(|step| step.1 == Mode::Walk));
+        assert!(path.contains(&((4, 4), Mode::Walk)));
+        assert_eq!(path.len(), 17);
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit a MultiModeMap state")]
+    fn test_pack_overflow_panics() {
+        let map = GridMap::new(3, 3);
+        let modes = MultiModeMap::new(&map, passable, 2);
+        modes.run((usize::MAX, 0), Mode::Walk, (Some(0), Some(0)));
+    }
+}
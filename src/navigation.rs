@@ -0,0 +1,137 @@
+use crate::{Cost, PathGenerator};
+use std::collections::{HashMap, HashSet};
+
+// (node at the other end, cost of the corridor)
+type Edge<C> = ((usize, usize), C);
+
+// junctions and dead ends of a region, joined by the corridors between them. It's a
+// PathGenerator itself, without a heuristic, so routes can be planned over it
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavigationGraph<C = usize> {
+    edges: HashMap<(usize, usize), Vec<Edge<C>>>,
+}
+
+impl<C: Cost> NavigationGraph<C> {
+    // sorted by position
+    pub fn nodes(&self) -> Vec<(usize, usize)> {
+        let mut nodes: Vec<_> = self.edges.keys().copied().collect();
+        nodes.sort();
+        nodes
+    }
+
+    // nodes at the other end of the corridors leaving node, with the cost of walking them
+    pub fn edges(&self, node: (usize, usize)) -> &[Edge<C>] {
+        self.edges.get(&node).map_or(&[], Vec::as_slice)
+    }
+}
+
+// cells of region with other than two neighbours in it become nodes, corridors of two
+// neighbour cells are walked from every node to the next one. Corridors are expected to be
+// walkable both ways, rings without any node in them are left out
+pub fn navigation_graph<C: Cost, T: PathGenerator<C>>(
+    from_struct: &T,
+    region: &HashSet<(usize, usize)>,
+) -> NavigationGraph<C> {
+    let neighbours = |position: (usize, usize)| -> Vec<(usize, usize)> {
+        let mut neighbours = from_struct.generate_paths(position);
+        neighbours.retain(|neighbour| region.contains(neighbour));
+        neighbours
+    };
+    let nodes: HashSet<_> = region
+        .iter()
+        .copied()
+        .filter(|position| neighbours(*position).len() != 2)
+        .collect();
+    let mut edges = HashMap::new();
+    for node in &nodes {
+        let node_edges: &mut Vec<Edge<C>> = edges.entry(*node).or_default();
+        for first in neighbours(*node) {
+            let (mut previous, mut position) = (*node, first);
+            let mut cost = from_struct.calculate_cost(previous, position);
+            while !nodes.contains(&position) {
+                let Some(next) = neighbours(position)
+                    .into_iter()
+                    .find(|next| *next != previous)
+                else {
+                    break;
+                };
+                cost = cost + from_struct.calculate_cost(position, next);
+                (previous, position) = (position, next);
+            }
+            if position == *node || !nodes.contains(&position) {
+                continue;
+            }
+            // two corridors between the same nodes, only the cheaper one is kept
+            match node_edges.iter_mut().find(|edge| edge.0 == position) {
+                Some(edge) => edge.1 = edge.1.min(cost),
+                None => node_edges.push((position, cost)),
+            }
+        }
+    }
+    NavigationGraph { edges }
+}
+
+impl<C: Cost> PathGenerator<C> for NavigationGraph<C> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.edges(from_position)
+            .iter()
+            .map(|edge| edge.0)
+            .collect()
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.edges(current_position)
+            .iter()
+            .find(|edge| edge.0 == next_position)
+            .expect("cost requested for an edge missing from the NavigationGraph")
+            .1
+    }
+
+    #[allow(unused_variables)]
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        C::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_navigation_graph() {
+        // ###.###
+        // ###.###
+        // .......
+        // ###.###
+        // ###.###
+        let mut map = GridMap::new(7, 5);
+        for x in 0..7 {
+            for y in 0..5 {
+                if y != 2 && x != 3 {
+                    map.block((x, y));
+                }
+            }
+        }
+        let region: HashSet<_> = (0..7)
+            .flat_map(|x| (0..5).map(move |y| (x, y)))
+            .filter(|cell| map.is_passable(*cell))
+            .collect();
+        let graph = navigation_graph(&map, &region);
+        assert_eq!(graph.nodes(), vec![(0, 2), (3, 0), (3, 2), (3, 4), (6, 2)]);
+        let mut junction = graph.edges((3, 2)).to_vec();
+        junction.sort();
+        assert_eq!(
+            junction,
+            vec![((0, 2), 3), ((3, 0), 2), ((3, 4), 2), ((6, 2), 3)]
+        );
+        assert_eq!(graph.edges((0, 2)), &[((3, 2), 3)]);
+
+        let route = AStar::run_result(&graph, (0, 2), (Some(3), Some(4))).unwrap();
+        assert_eq!((route.path, route.cost), (vec![(3, 4), (3, 2), (0, 2)], 5));
+    }
+}
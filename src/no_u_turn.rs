@@ -0,0 +1,101 @@
+use crate::{AStar, Cost, PathGenerator};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+// a cell reached from different neighbours is a different state here, (position, previous)
+type State = ((usize, usize), Option<(usize, usize)>);
+
+impl<C: Cost> AStar<C> {
+    // never steps straight back to the cell it just came from, came_from is where the search
+    // is heading from at start, e.g. the cell behind a vehicle. Paths are ordered like in run
+    pub fn run_no_u_turn<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        came_from: Option<(usize, usize)>,
+    ) -> Option<Vec<(usize, usize)>> {
+        let mut closed: HashSet<State> = HashSet::new();
+        let mut previous_state: HashMap<State, State> = HashMap::new();
+        let mut que = BinaryHeap::new();
+        let heuristic_cost = from_struct.calculate_heuristic_cost(start, target);
+        que.push(Reverse((
+            heuristic_cost,
+            C::default(),
+            start,
+            came_from,
+            None,
+        )));
+        while let Some(Reverse((_, cost, position, came_from, previous))) = que.pop() {
+            let state = (position, came_from);
+            if !closed.insert(state) {
+                continue;
+            }
+            if let Some(previous) = previous {
+                previous_state.insert(state, previous);
+            }
+            if Self::target_is_reached(target, &position) {
+                let mut path = vec![position];
+                let mut state = state;
+                while let Some(previous) = previous_state.get(&state) {
+                    path.push(previous.0);
+                    state = *previous;
+                }
+                return Some(path);
+            }
+            for possible_path in from_struct.generate_paths(position) {
+                let next_state = (possible_path, Some(position));
+                if Some(possible_path) == came_from || closed.contains(&next_state) {
+                    continue;
+                }
+                let edge_cost = from_struct.calculate_cost(position, possible_path);
+                if edge_cost.is_infinite() {
+                    continue;
+                }
+                let new_cost = cost + edge_cost;
+                let total_cost =
+                    new_cost + from_struct.calculate_heuristic_cost(possible_path, target);
+                que.push(Reverse((
+                    total_cost,
+                    new_cost,
+                    possible_path,
+                    Some(position),
+                    Some(state),
+                )));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::Graph;
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_no_u_turn() {
+        let map = GridMap::new(5, 3);
+        let target = (Some(0), Some(1));
+        assert_eq!(AStar::run(&map, (2, 1), target).unwrap().len(), 3);
+
+        // heading east, so the target behind can only be reached by turning around a block
+        let path = AStar::run_no_u_turn(&map, (2, 1), target, Some((1, 1))).unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.first(), Some(&(0, 1)));
+        assert_eq!(path.last(), Some(&(2, 1)));
+        assert_ne!(path[path.len() - 2], (1, 1));
+        assert!(path.windows(3).all(|steps| steps[0] != steps[2]));
+
+        let path = AStar::run_no_u_turn(&map, (2, 1), target, None).unwrap();
+        assert_eq!(path, vec![(0, 1), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn test_infinite_edge_is_skipped() {
+        let graph = Graph::new(vec![(0, 1, usize::MAX), (0, 2, 1)]);
+        assert_eq!(
+            AStar::run_no_u_turn(&graph, (0, 0), (Some(1), Some(0)), None),
+            None
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{Direction, PathGenerator};
+
+// moves are the offsets listed with their costs, e.g. knight jumps, a move is allowed when it
+// stays inside usize and passable holds for the landing cell, cells in between are not checked
+pub struct OffsetMap<F> {
+    offsets: Vec<(Direction, usize)>,
+    passable: F,
+}
+
+impl<F: Fn((usize, usize)) -> bool> OffsetMap<F> {
+    pub fn new(offsets: Vec<(Direction, usize)>, passable: F) -> Self {
+        Self { offsets, passable }
+    }
+
+    fn apply(position: (usize, usize), offset: Direction) -> Option<(usize, usize)> {
+        let x = position
+            .0
+            .checked_add_signed(isize::try_from(offset.0).ok()?)?;
+        let y = position
+            .1
+            .checked_add_signed(isize::try_from(offset.1).ok()?)?;
+        Some((x, y))
+    }
+}
+
+impl<F: Fn((usize, usize)) -> bool> PathGenerator for OffsetMap<F> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut possible_paths = Vec::new();
+        for (offset, _) in &self.offsets {
+            if let Some(position) = Self::apply(from_position, *offset) {
+                if (self.passable)(position) && !possible_paths.contains(&position) {
+                    possible_paths.push(position);
+                }
+            }
+        }
+        possible_paths
+    }
+
+    // the cheapest offset leading from current to next
+    fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> usize {
+        self.offsets
+            .iter()
+            .filter(|(offset, _)| Self::apply(current_position, *offset) == Some(next_position))
+            .map(|(_, cost)| *cost)
+            .min()
+            .expect("cost requested for a move missing from the offsets")
+    }
+
+    // a move covers at most the longest offset along either axis, so the heuristic counts the
+    // moves needed for that at the cheapest cost
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> usize {
+        let reach = self
+            .offsets
+            .iter()
+            .map(|(offset, _)| offset.0.unsigned_abs().max(offset.1.unsigned_abs()))
+            .max()
+            .unwrap_or(0) as usize;
+        let cheapest = self.offsets.iter().map(|(_, cost)| *cost).min();
+        let distance = target
+            .0
+            .map_or(0, |x| x.abs_diff(position.0))
+            .max(target.1.map_or(0, |y| y.abs_diff(position.1)));
+        match cheapest {
+            Some(cheapest) if reach > 0 => distance.div_ceil(reach) * cheapest,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::assert_optimal;
+    use crate::AStar;
+
+    fn knight_moves() -> Vec<(Direction, usize)> {
+        [
+            (1, 2),
+            (2, 1),
+            (2, -1),
+            (1, -2),
+            (-1, -2),
+            (-2, -1),
+            (-2, 1),
+            (-1, 2),
+        ]
+        .into_iter()
+        .map(|offset| (offset, 1))
+        .collect()
+    }
+
+    #[test]
+    fn test_knight_moves() {
+        let board = OffsetMap::new(knight_moves(), |position: (usize, usize)| {
+            position.0 < 8 && position.1 < 8
+        });
+        assert_eq!(board.generate_paths((0, 0)), vec![(1, 2), (2, 1)]);
+        let path = AStar::run(&board, (0, 0), (Some(7), Some(7))).unwrap();
+        assert_eq!(path.len(), 7);
+        assert_optimal(&board, (0, 0), (7, 7), &path);
+        for pair in path.windows(2) {
+            let jump = (pair[0].0.abs_diff(pair[1].0), pair[0].1.abs_diff(pair[1].1));
+            assert!(jump == (1, 2) || jump == (2, 1));
+        }
+    }
+
+    #[test]
+    fn test_passability_and_costs() {
+        // of the two jumps to (3, 3) the one over (1, 2) lands on a blocked cell
+        let board = OffsetMap::new(knight_moves(), |position: (usize, usize)| {
+            position.0 < 8 && position.1 < 8 && position != (1, 2)
+        });
+        assert_eq!(
+            AStar::run(&board, (0, 0), (Some(3), Some(3))),
+            Some(vec![(3, 3), (2, 1), (0, 0)])
+        );
+        assert!(AStar::run(&board, (0, 0), (Some(1), Some(2))).is_none());
+
+        let mut moves = knight_moves();
+        moves.push(((1, 1), 5));
+        let board = OffsetMap::new(moves, |position: (usize, usize)| position.0 < 3);
+        assert_eq!(board.calculate_cost((0, 0), (1, 1)), 5);
+        // two diagonal steps for 10 lose against four knight jumps
+        let result = AStar::run_result(&board, (0, 0), (Some(2), Some(2))).unwrap();
+        assert_eq!((result.path.len(), result.cost), (5, 4));
+    }
+}
@@ -0,0 +1,125 @@
+use crate::reverse::Reversed;
+use crate::sweep::sweep;
+use crate::{AStar, PathGenerator};
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+impl AStar {
+    // approximate, the best route is NP-hard to find: from where it stands the route heads for
+    // the uncollected reward with the most reward per cost that still leaves budget to get to
+    // goal, until none is left, then it goes to goal. Rewards on the way are collected too,
+    // ones of 0 or less are never sought. Ordered like in run, None when goal can't be reached
+    // within budget
+    pub fn run_orienteering<T: PathGenerator>(
+        from_struct: &T,
+        start: (usize, usize),
+        rewards: &[((usize, usize), i64)],
+        budget: usize,
+        goal: Option<(usize, usize)>,
+    ) -> Option<Vec<(usize, usize)>> {
+        let to_goal = goal.map(|goal| sweep(&Reversed { inner: from_struct }, goal, None));
+        let cost_to_goal = |position| match &to_goal {
+            Some(to_goal) => to_goal.get(&position).map(|node| node.cost),
+            None => Some(0),
+        };
+        if cost_to_goal(start)? > budget {
+            return None;
+        }
+        let mut collected: Vec<bool> = rewards.iter().map(|reward| reward.1 <= 0).collect();
+        let mut route = vec![start];
+        let mut spent = 0;
+        loop {
+            let sought: Vec<_> = rewards
+                .iter()
+                .zip(&collected)
+                .filter(|(_, collected)| !**collected)
+                .map(|(reward, _)| reward.0)
+                .collect();
+            let settled = sweep(from_struct, *route.last()?, Some(&sought));
+            let next = rewards
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !collected[*index])
+                .filter_map(|(_, (position, reward))| {
+                    let node = settled.get(position)?;
+                    let total = spent + node.cost + cost_to_goal(*position)?;
+                    (total <= budget).then_some((node, *reward as f64 / node.cost as f64))
+                })
+                .max_by(|a, b| {
+                    a.1.partial_cmp(&b.1)
+                        .unwrap_or(Ordering::Equal)
+                        .then(b.0.cost.cmp(&a.0.cost))
+                });
+            let Some((node, _)) = next else {
+                break;
+            };
+            spent += node.cost;
+            let mut leg = Self::reconstruct_path(Rc::clone(node));
+            leg.reverse();
+            for position in &leg {
+                for (index, reward) in rewards.iter().enumerate() {
+                    if reward.0 == *position {
+                        collected[index] = true;
+                    }
+                }
+            }
+            route.extend(&leg[1..]);
+        }
+        if let Some(to_goal) = &to_goal {
+            let last = &to_goal[route.last()?];
+            route.extend(&Self::reconstruct_path(Rc::clone(last))[1..]);
+        }
+        route.reverse();
+        Some(route)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AStar, GridMap, PathGenerator};
+
+    fn route_cost(map: &GridMap, route: &[(usize, usize)]) -> usize {
+        route
+            .windows(2)
+            .map(|pair| map.calculate_cost(pair[1], pair[0]))
+            .sum()
+    }
+
+    #[test]
+    fn test_run_orienteering() {
+        let map = GridMap::new(10, 10);
+        let rewards = [
+            ((2, 0), 3),
+            ((4, 0), 3),
+            ((4, 3), 5),
+            ((9, 9), 100),
+            ((0, 4), -5),
+        ];
+        let collected = |route: &[(usize, usize)]| -> i64 {
+            rewards
+                .iter()
+                .filter(|reward| route.contains(&reward.0))
+                .map(|reward| reward.1)
+                .sum()
+        };
+
+        let route = AStar::run_orienteering(&map, (0, 0), &rewards, 12, Some((0, 0))).unwrap();
+        assert_eq!(
+            (route.first(), route.last()),
+            (Some(&(0, 0)), Some(&(0, 0)))
+        );
+        assert!(route_cost(&map, &route) <= 12);
+        assert_eq!(collected(&route), 6);
+
+        let route = AStar::run_orienteering(&map, (0, 0), &rewards, 18, None).unwrap();
+        assert_eq!(route.first(), Some(&(9, 9)));
+        assert!(collected(&route) >= 100);
+        assert!(route_cost(&map, &route) <= 18);
+
+        assert!(AStar::run_orienteering(&map, (0, 0), &rewards, 5, Some((5, 5))).is_none());
+        let route = AStar::run_orienteering(&map, (0, 0), &rewards, 10, Some((5, 5))).unwrap();
+        assert_eq!(route.first(), Some(&(5, 5)));
+        assert!(route_cost(&map, &route) <= 10);
+        assert!(collected(&route) >= 5);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{AStar, Cost, PathGenerator};
+use std::collections::HashMap;
+use std::thread;
+
+type Target = (Option<usize>, Option<usize>);
+
+// splits jobs over scoped threads, every job is searched on its own and written back to its
+// index so the output is the same as running them one after the other whatever the scheduling
+fn in_parallel<J: Sync, R: Send>(jobs: &[J], run: impl Fn(&J) -> R + Sync) -> Vec<R> {
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    let chunk_size = jobs.len().div_ceil(threads).max(1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = jobs
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(&run).collect::<Vec<_>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("a parallel search panicked"))
+            .collect()
+    })
+}
+
+impl<C: Cost + Send> AStar<C> {
+    // one run per (start, target), in the order of jobs
+    pub fn run_many<T: PathGenerator<C> + Sync>(
+        from_struct: &T,
+        jobs: &[((usize, usize), Target)],
+    ) -> Vec<Option<Vec<(usize, usize)>>> {
+        in_parallel(jobs, |(start, target)| {
+            AStar::run(from_struct, *start, *target)
+        })
+    }
+
+    // one cost_field per start, in the order of starts
+    pub fn cost_fields<T: PathGenerator<C> + Sync>(
+        from_struct: &T,
+        starts: &[(usize, usize)],
+    ) -> Vec<HashMap<(usize, usize), C>> {
+        in_parallel(starts, |start| AStar::cost_field(from_struct, *start))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_run_many_is_deterministic() {
+        let map = GridMap::builder(12, 12)
+            .block_all((0..10).map(|y| (5, y)))
+            .build();
+        let jobs: Vec<_> = (0..24)
+            .map(|i| ((i % 12, i / 2), (Some(11 - i % 12), Some(11 - i / 2))))
+            .collect();
+        let sequential: Vec<_> = jobs
+            .iter()
+            .map(|(start, target)| AStar::run(&map, *start, *target))
+            .collect();
+        for _ in 0..20 {
+            assert_eq!(AStar::run_many(&map, &jobs), sequential);
+        }
+        assert!(AStar::run_many(&map, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_cost_fields() {
+        let map = GridMap::builder(6, 6).block((2, 2)).build();
+        let starts = [(0, 0), (5, 5), (2, 3)];
+        let fields = AStar::cost_fields(&map, &starts);
+        for (start, field) in starts.iter().zip(&fields) {
+            assert_eq!(*field, AStar::cost_field(&map, *start));
+        }
+        assert_eq!(fields.len(), 3);
+    }
+}
@@ -0,0 +1,292 @@
+use crate::{AStar, Cost, PathGenerator};
+
+// a cell with the step that led into it
+type Directed = ((usize, usize), Option<(i64, i64)>);
+type Split = (Vec<(usize, usize)>, Vec<(usize, usize)>);
+
+// a move onto one of the 8 cells around or any longer one, e.g. through a Teleporter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step<C = usize> {
+    Walk((usize, usize)),
+    Jump {
+        from: (usize, usize),
+        to: (usize, usize),
+        cost: C,
+    },
+}
+
+// first and last cell plus every cell where the step changes, including a change between a
+// diagonal and an orthogonal step, in the order of path
+pub fn extract_corners(path: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let step = |from: (usize, usize), to: (usize, usize)| {
+        (to.0 as i64 - from.0 as i64, to.1 as i64 - from.1 as i64)
+    };
+    let mut corners: Vec<(usize, usize)> = path.first().copied().into_iter().collect();
+    for cells in path.windows(3) {
+        if step(cells[0], cells[1]) != step(cells[1], cells[2]) {
+            corners.push(cells[1]);
+        }
+    }
+    if path.len() > 1 {
+        corners.push(path[path.len() - 1]);
+    }
+    corners
+}
+
+// extract_corners, enough for decompress_path to give path back when every step of it goes to
+// one of the 8 cells around, which steps over the edge of a wrapping GridMap don't
+pub fn compress_path(path: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    extract_corners(path)
+}
+
+// fills the straight lines between the corners of compress_path back in
+pub fn decompress_path(corners: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut path: Vec<(usize, usize)> = corners.first().copied().into_iter().collect();
+    for pair in corners.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let steps = from.0.abs_diff(to.0).max(from.1.abs_diff(to.1));
+        for step in 1..=steps {
+            let along = |from: usize, to: usize| {
+                if to >= from {
+                    from + (to - from) * step / steps
+                } else {
+                    from - (from - to) * step / steps
+                }
+            };
+            path.push((along(from.0, to.0), along(from.1, to.1)));
+        }
+    }
+    path
+}
+
+// turns a path from run around to go from start to target, or back, without a new Vec
+pub fn reverse_path_in_place(path: &mut [(usize, usize)]) {
+    path.reverse();
+}
+
+// whether the path walks from a straight to b, path is ordered like in run so b comes right
+// before a in it. For undirected edges check both is_edge_on_path(path, a, b) and (path, b, a)
+pub fn is_edge_on_path(path: &[(usize, usize)], a: (usize, usize), b: (usize, usize)) -> bool {
+    path.windows(2).any(|pair| pair[0] == b && pair[1] == a)
+}
+
+// path goes from start to target like run_ordered, the first part holds the cells reached
+// within budget and the second the rest, together they are path
+pub fn split_path_at_cost<C: Cost, T: PathGenerator<C>>(
+    from_struct: &T,
+    path: &[(usize, usize)],
+    budget: C,
+) -> Split {
+    let mut spent = C::default();
+    let mut reached = path.len().min(1);
+    for pair in path.windows(2) {
+        spent = spent + from_struct.calculate_cost(pair[0], pair[1]);
+        if spent > budget {
+            break;
+        }
+        reached += 1;
+    }
+    (path[..reached].to_vec(), path[reached..].to_vec())
+}
+
+impl<C: Cost> AStar<C> {
+    // path from start to target, every cell with the step that led into it, None for start
+    pub fn run_with_directions<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> Option<Vec<Directed>> {
+        let path = Self::run_ordered(from_struct, start, target)?;
+        let mut previous = None;
+        Some(
+            path.into_iter()
+                .map(|position| {
+                    let direction = previous.map(|from: (usize, usize)| {
+                        (
+                            position.0 as i64 - from.0 as i64,
+                            position.1 as i64 - from.1 as i64,
+                        )
+                    });
+                    previous = Some(position);
+                    (position, direction)
+                })
+                .collect(),
+        )
+    }
+
+    // the moves from start to goal, each one the step from the previous cell, empty when
+    // start is the goal
+    pub fn run_relative<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        goal: (usize, usize),
+    ) -> Option<Vec<(i64, i64)>> {
+        let steps = Self::run_with_directions(from_struct, start, (Some(goal.0), Some(goal.1)))?;
+        Some(steps.into_iter().filter_map(|step| step.1).collect())
+    }
+
+    // path from start to target, the start is a Walk onto itself
+    pub fn run_annotated<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> Option<Vec<Step<C>>> {
+        let path = Self::run_ordered(from_struct, start, target)?;
+        let mut steps = vec![Step::Walk(path[0])];
+        for pair in path.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            if from.0.abs_diff(to.0) <= 1 && from.1.abs_diff(to.1) <= 1 {
+                steps.push(Step::Walk(to));
+            } else {
+                let cost = from_struct.calculate_cost(from, to);
+                steps.push(Step::Jump { from, to, cost });
+            }
+        }
+        Some(steps)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::Graph;
+    use crate::{GridMap, Teleporter};
+
+    #[test]
+    fn test_extract_corners() {
+        let path = [
+            (0, 0),
+            (1, 0),
+            (2, 0),
+            (3, 1),
+            (4, 2),
+            (4, 3),
+            (4, 4),
+            (3, 4),
+        ];
+        assert_eq!(
+            extract_corners(&path),
+            vec![(0, 0), (2, 0), (4, 2), (4, 4), (3, 4)]
+        );
+        assert_eq!(extract_corners(&path[..3]), vec![(0, 0), (2, 0)]);
+        assert_eq!(extract_corners(&[(5, 5)]), vec![(5, 5)]);
+        assert!(extract_corners(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_compress_round_trip() {
+        let map = GridMap::builder(12, 9)
+            .diagonal(true)
+            .block_all((0..7).map(|y| (5, y)))
+            .build();
+        let path = AStar::run(&map, (0, 0), (Some(11), Some(1))).unwrap();
+        let corners = compress_path(&path);
+        assert!(corners.len() < path.len());
+        assert_eq!(decompress_path(&corners), path);
+
+        let path = AStar::run(&GridMap::new(4, 4), (3, 3), (Some(0), Some(0))).unwrap();
+        assert_eq!(decompress_path(&compress_path(&path)), path);
+        assert_eq!(decompress_path(&compress_path(&[(2, 2)])), vec![(2, 2)]);
+        assert!(decompress_path(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_reverse_path_in_place() {
+        let map = GridMap::builder(4, 3).block_all([(1, 0), (1, 1)]).build();
+        let target = (Some(2), Some(0));
+        let mut path = AStar::run(&map, (0, 0), target).unwrap();
+        let capacity = path.capacity();
+        reverse_path_in_place(&mut path);
+        assert_eq!(Some(path.clone()), AStar::run_ordered(&map, (0, 0), target));
+        assert_eq!((path[0], path.capacity()), ((0, 0), capacity));
+    }
+
+    #[test]
+    fn test_run_with_directions() {
+        // L shaped corridor, down column 0 and then along row 2
+        let map = GridMap::builder(3, 3)
+            .block_all([(1, 0), (2, 0), (1, 1), (2, 1)])
+            .build();
+        let steps = AStar::run_with_directions(&map, (0, 0), (Some(2), Some(2))).unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                ((0, 0), None),
+                ((0, 1), Some((0, 1))),
+                ((0, 2), Some((0, 1))),
+                ((1, 2), Some((1, 0))),
+                ((2, 2), Some((1, 0))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_relative() {
+        let map = GridMap::builder(5, 4)
+            .block_all([(1, 0), (1, 1), (3, 2), (3, 3)])
+            .build();
+        let moves = AStar::run_relative(&map, (0, 0), (4, 3)).unwrap();
+        let mut position: (usize, usize) = (0, 0);
+        let mut replayed = vec![position];
+        for (dx, dy) in moves {
+            position = (
+                position.0.checked_add_signed(dx as isize).unwrap(),
+                position.1.checked_add_signed(dy as isize).unwrap(),
+            );
+            replayed.push(position);
+        }
+        assert_eq!(
+            Some(replayed),
+            AStar::run_ordered(&map, (0, 0), (Some(4), Some(3)))
+        );
+        assert_eq!(AStar::run_relative(&map, (2, 2), (2, 2)), Some(vec![]));
+    }
+
+    #[test]
+    fn test_split_path_at_cost() {
+        let graph = Graph::new(vec![(0, 1, 2), (1, 2, 5), (2, 3, 1), (3, 4, 4)]);
+        let path = AStar::run_ordered(&graph, (0, 0), (Some(4), Some(0))).unwrap();
+        let (reached, rest) = split_path_at_cost(&graph, &path, 8);
+        assert_eq!(reached, vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+        assert_eq!(rest, vec![(4, 0)]);
+
+        let (reached, rest) = split_path_at_cost(&graph, &path, 6);
+        assert_eq!(reached, vec![(0, 0), (1, 0)]);
+        assert_eq!(rest.len(), 3);
+        assert_eq!(split_path_at_cost(&graph, &path, 0).0, vec![(0, 0)]);
+        assert_eq!(split_path_at_cost(&graph, &path, 12).0, path);
+        assert_eq!(split_path_at_cost(&graph, &[], 3), (vec![], vec![]));
+    }
+
+    #[test]
+    fn test_run_annotated() {
+        let teleporter = Teleporter::new(GridMap::new(12, 1), (2, 0), (9, 0), 3);
+        let steps = AStar::run_annotated(&teleporter, (0, 0), (Some(11), Some(0))).unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                Step::Walk((0, 0)),
+                Step::Walk((1, 0)),
+                Step::Walk((2, 0)),
+                Step::Jump {
+                    from: (2, 0),
+                    to: (9, 0),
+                    cost: 3
+                },
+                Step::Walk((10, 0)),
+                Step::Walk((11, 0)),
+            ]
+        );
+        let steps = AStar::run_annotated(&GridMap::new(3, 3), (0, 0), (Some(2), Some(2))).unwrap();
+        assert!(steps.iter().all(|step| matches!(step, Step::Walk(_))));
+    }
+
+    #[test]
+    fn test_is_edge_on_path() {
+        let path = AStar::run(&GridMap::new(4, 1), (0, 0), (Some(3), Some(0))).unwrap();
+        assert!(is_edge_on_path(&path, (1, 0), (2, 0)));
+        assert!(!is_edge_on_path(&path, (2, 0), (1, 0)));
+        assert!(!is_edge_on_path(&path, (0, 0), (2, 0)));
+        assert!(!is_edge_on_path(&path[..1], (2, 0), (3, 0)));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::PathGenerator;
+
+// PathGenerator with float costs, searched through Quantized
+pub trait FloatPathGenerator {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)>;
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> f64;
+    fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> f64;
+}
+
+// edge costs of inner times scale rounded to usize, the heuristic times scale rounded down.
+// Every step can be off by half a unit, so the path found can cost up to steps / scale more
+// than the optimal one in float costs, and a heuristic that's tight in floats can overestimate
+// the rounded costs by as much. Larger scales make both smaller but costs have to fit in usize
+pub struct Quantized<G> {
+    inner: G,
+    scale: f64,
+}
+
+impl<G: FloatPathGenerator> Quantized<G> {
+    pub fn new(inner: G, scale: f64) -> Self {
+        assert!(scale > 0.0, "Quantized needs a positive scale");
+        Self { inner, scale }
+    }
+
+    // cost back in the units of inner
+    pub fn to_float(&self, cost: usize) -> f64 {
+        cost as f64 / self.scale
+    }
+}
+
+impl<G: FloatPathGenerator> PathGenerator for Quantized<G> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner.generate_paths(from_position)
+    }
+
+    fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> usize {
+        let cost = self.inner.calculate_cost(current_position, next_position);
+        (cost * self.scale).round() as usize
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> usize {
+        let heuristic_cost = self.inner.calculate_heuristic_cost(position, target);
+        (heuristic_cost * self.scale).floor() as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap};
+    use std::collections::HashMap;
+
+    // 8-connected rough terrain, a step costs its length times the roughness of the cell
+    // entered
+    struct Rough(GridMap);
+
+    impl Rough {
+        fn roughness(position: (usize, usize)) -> f64 {
+            1.0 + 0.35 * ((position.0 * 3 + position.1) % 4) as f64
+        }
+    }
+
+    impl FloatPathGenerator for Rough {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.0.generate_paths(from_position)
+        }
+
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> f64 {
+            let diagonal =
+                current_position.0 != next_position.0 && current_position.1 != next_position.1;
+            let length = if diagonal {
+                std::f64::consts::SQRT_2
+            } else {
+                1.0
+            };
+            length * Self::roughness(next_position)
+        }
+
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> f64 {
+            let dx = target.0.map_or(0, |x| x.abs_diff(position.0)) as f64;
+            let dy = target.1.map_or(0, |y| y.abs_diff(position.1)) as f64;
+            dx.max(dy) + (std::f64::consts::SQRT_2 - 1.0) * dx.min(dy)
+        }
+    }
+
+    fn float_optimum(map: &Rough, start: (usize, usize), goal: (usize, usize)) -> f64 {
+        let mut best = HashMap::from([(start, 0.0_f64)]);
+        let mut open = vec![start];
+        while let Some(index) =
+            (0..open.len()).min_by(|a, b| best[&open[*a]].total_cmp(&best[&open[*b]]))
+        {
+            let position = open.swap_remove(index);
+            for next in map.generate_paths(position) {
+                let cost = best[&position] + map.calculate_cost(position, next);
+                if best.get(&next).is_none_or(|known| cost < *known) {
+                    best.insert(next, cost);
+                    open.push(next);
+                }
+            }
+        }
+        best[&goal]
+    }
+
+    #[test]
+    fn test_quantized_matches_float() {
+        let map = || {
+            Rough(
+                GridMap::builder(9, 7)
+                    .diagonal(true)
+                    .block_all([(4, 1), (4, 2), (4, 3), (4, 4), (2, 5), (6, 2)])
+                    .build(),
+            )
+        };
+        let (start, goal) = ((0, 3), (8, 2));
+        let optimum = float_optimum(&map(), start, goal);
+        for scale in [1.0, 10.0, 1000.0] {
+            let quantized = Quantized::new(map(), scale);
+            let path = AStar::run(&quantized, start, (Some(goal.0), Some(goal.1))).unwrap();
+            let float_cost: f64 = path
+                .windows(2)
+                .map(|pair| quantized.inner.calculate_cost(pair[1], pair[0]))
+                .sum();
+            let error = path.len() as f64 / scale;
+            assert!(float_cost >= optimum - 1e-9);
+            assert!(
+                float_cost <= optimum + error,
+                "{float_cost} {optimum} {scale}"
+            );
+        }
+        let fine = Quantized::new(map(), 1000.0);
+        let result = AStar::run_result(&fine, start, (Some(goal.0), Some(goal.1))).unwrap();
+        assert!((fine.to_float(result.cost) - optimum).abs() < 0.01);
+    }
+}
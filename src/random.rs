@@ -0,0 +1,99 @@
+use crate::PathResult;
+use crate::{AStar, Cost, PathGenerator};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+impl<C: Cost> AStar<C> {
+    // equal cost nodes are ordered by values drawn from rng, the same seed gives the same path
+    pub fn run_with_rng<T: PathGenerator<C>, R: RngCore>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        rng: &mut R,
+    ) -> Option<Vec<(usize, usize)>> {
+        let mut inst = Self::new(target);
+        let goal = inst.search_with(
+            from_struct,
+            start,
+            || rng.next_u64(),
+            |node| Self::target_is_reached(target, &node.position),
+        )?;
+        Some(Self::reconstruct_path(goal))
+    }
+
+    // run_with_rng on a StdRng from seed, a drawn one when seed is None, the result keeps the
+    // seed so the run can be repeated
+    pub fn run_seeded<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        seed: Option<u64>,
+    ) -> Option<PathResult<C>> {
+        let seed = seed.unwrap_or_else(rand::random);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut inst = Self::new(target);
+        let goal = inst.search_with(
+            from_struct,
+            start,
+            || rng.next_u64(),
+            |node| Self::target_is_reached(target, &node.position),
+        )?;
+        let cost = goal.cost;
+        let path = Self::reconstruct_path(goal);
+        Some(PathResult {
+            stats: inst.stats(Some(&path)),
+            path,
+            cost,
+            optimal: true,
+            seed: Some(seed),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AStar, GridMap};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_seeded_rng_is_reproducible() {
+        let map = GridMap::new(6, 6);
+        let target = (Some(5), Some(5));
+        let mut paths = HashSet::new();
+        for seed in 0..20 {
+            let path = AStar::run_with_rng(&map, (0, 0), target, &mut StdRng::seed_from_u64(seed));
+            let repeated =
+                AStar::run_with_rng(&map, (0, 0), target, &mut StdRng::seed_from_u64(seed));
+            assert_eq!(path, repeated);
+            assert_eq!(path.as_ref().unwrap().len(), 11);
+            paths.insert(path.unwrap());
+        }
+        assert!(paths.len() > 1);
+    }
+
+    #[test]
+    fn test_run_seeded_records_seed() {
+        let map = GridMap::new(8, 8);
+        let target = (Some(7), Some(7));
+        let mut paths = HashSet::new();
+        for _ in 0..10 {
+            let result = AStar::run_seeded(&map, (0, 0), target, None).unwrap();
+            let replayed = AStar::run_seeded(&map, (0, 0), target, result.seed).unwrap();
+            assert_eq!(
+                (&replayed.path, replayed.cost, replayed.seed),
+                (&result.path, result.cost, result.seed)
+            );
+            paths.insert(result.path);
+        }
+        assert!(paths.len() > 1);
+        let result = AStar::run_seeded(&map, (0, 0), target, Some(7)).unwrap();
+        assert_eq!(result.seed, Some(7));
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(
+            AStar::run_with_rng(&map, (0, 0), target, &mut rng),
+            Some(result.path)
+        );
+    }
+}
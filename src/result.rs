@@ -0,0 +1,209 @@
+use crate::{AStar, Cost, PathGenerator, SearchStats};
+use std::collections::HashSet;
+
+/// Dropping a result unread is almost always a bug, so it triggers `unused_must_use`:
+///
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// use a_star_traitbased::{AStar, GridMap};
+///
+/// let map = GridMap::new(3, 1);
+/// AStar::run_result(&map, (0, 0), (Some(2), Some(0))).unwrap();
+/// ```
+#[must_use]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathResult<C = usize> {
+    // ordered like the path returned by run, from the target back to the start
+    pub path: Vec<(usize, usize)>,
+    pub cost: C,
+    // what the search took to find the path
+    pub stats: SearchStats,
+    // false when the search traded optimality for speed, like AraStar with a weight above 1,
+    // true still relies on an admissible heuristic
+    pub optimal: bool,
+    // the seed ties were broken with, run_seeded given it again gives the same result
+    pub seed: Option<u64>,
+}
+
+impl<C> PathResult<C> {
+    // for repeated "is this cell on the path" lookups
+    pub fn cell_set(&self) -> HashSet<(usize, usize)> {
+        self.path.iter().copied().collect()
+    }
+}
+
+impl<C: Cost + Into<f64>> PathResult<C> {
+    pub fn cost_as_f64(&self) -> f64 {
+        self.cost.into()
+    }
+}
+
+impl<C: Cost> AStar<C> {
+    pub fn run_result<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> Option<PathResult<C>> {
+        let mut inst = Self::new(target);
+        let goal = inst.search(from_struct, start)?;
+        let cost = goal.cost;
+        let path = Self::reconstruct_path(goal);
+        Some(PathResult {
+            stats: inst.stats(Some(&path)),
+            path,
+            cost,
+            optimal: true,
+            seed: None,
+        })
+    }
+
+    pub fn shortest_cost<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        goal: (usize, usize),
+    ) -> Option<C> {
+        let mut inst = Self::new((Some(goal.0), Some(goal.1)));
+        inst.search(from_struct, start).map(|goal| goal.cost)
+    }
+}
+
+impl AStar {
+    // the path is only returned when its cost is at most max_detour_ratio times the heuristic
+    // from start to goal, which is taken as the cost of going there in a straight line
+    pub fn run_if_efficient<T: PathGenerator>(
+        from_struct: &T,
+        start: (usize, usize),
+        goal: (usize, usize),
+        max_detour_ratio: f64,
+    ) -> Option<Vec<(usize, usize)>> {
+        let target = (Some(goal.0), Some(goal.1));
+        let result = Self::run_result(from_struct, start, target)?;
+        let straight_line_cost = from_struct.calculate_heuristic_cost(start, target);
+        (result.cost as f64 <= max_detour_ratio * straight_line_cost as f64).then_some(result.path)
+    }
+
+    // every cell of the path with the cost left from it to the target, ordered like in run so
+    // it starts with (target, 0)
+    pub fn run_with_remaining<T: PathGenerator>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> Option<Vec<((usize, usize), usize)>> {
+        let mut inst = Self::new(target);
+        let goal = inst.search(from_struct, start)?;
+        let total = goal.cost;
+        let steps = Self::reconstruct_step_costs(goal);
+        Some(
+            steps
+                .into_iter()
+                .map(|(position, cost)| (position, total - cost))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AStar, GridMap, MilliCost, PathGenerator};
+
+    struct MilliMap(GridMap);
+
+    impl PathGenerator<MilliCost> for MilliMap {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.0.generate_paths(from_position)
+        }
+
+        #[allow(unused_variables)]
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> MilliCost {
+            MilliCost(1500)
+        }
+
+        #[allow(unused_variables)]
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> MilliCost {
+            MilliCost::default()
+        }
+    }
+
+    #[test]
+    fn test_cost_as_f64() {
+        let map = MilliMap(GridMap::new(4, 1));
+        let result = AStar::run_result(&map, (0, 0), (Some(3), Some(0))).unwrap();
+        assert_eq!(result.path.len(), 4);
+        assert_eq!(result.cost, MilliCost(4500));
+        assert_eq!(result.cost_as_f64(), 4.5);
+    }
+
+    #[test]
+    fn test_fields() {
+        let map = GridMap::builder(4, 3).block_all([(1, 0), (1, 1)]).build();
+        let result = AStar::run_result(&map, (0, 0), (Some(2), Some(0))).unwrap();
+        assert_eq!(result.path.first(), Some(&(2, 0)));
+        assert_eq!(result.cost, 6);
+        assert_eq!(result.stats.path_len, 6);
+        assert!(result.stats.expanded >= 6);
+        assert!(result.optimal);
+    }
+
+    #[test]
+    fn test_cell_set() {
+        let map = GridMap::builder(3, 3).block((1, 0)).build();
+        let result = AStar::run_result(&map, (0, 0), (Some(2), Some(0))).unwrap();
+        let cells = result.cell_set();
+        assert_eq!(cells.len(), result.path.len());
+        assert!(cells.contains(&(0, 0)) && cells.contains(&(1, 1)) && cells.contains(&(2, 0)));
+        assert!(!cells.contains(&(1, 0)) && !cells.contains(&(0, 2)));
+    }
+
+    #[test]
+    fn test_run_with_remaining() {
+        let graph = crate::test::Graph::new(vec![(0, 1, 4), (1, 2, 1), (2, 3, 2), (0, 3, 9)]);
+        let steps = AStar::run_with_remaining(&graph, (0, 0), (Some(3), Some(0))).unwrap();
+        assert_eq!(
+            steps,
+            vec![((3, 0), 0), ((2, 0), 2), ((1, 0), 3), ((0, 0), 7)]
+        );
+
+        let map = GridMap::builder(5, 4)
+            .block_all([(2, 0), (2, 1), (2, 2)])
+            .build();
+        let target = (Some(4), Some(0));
+        let steps = AStar::run_with_remaining(&map, (0, 0), target).unwrap();
+        let path: Vec<_> = steps.iter().map(|step| step.0).collect();
+        assert_eq!(Some(path), AStar::run(&map, (0, 0), target));
+        // walked from the start the remaining cost goes down to 0 at the goal
+        assert!(steps.windows(2).all(|pair| pair[0].1 < pair[1].1));
+        assert_eq!(steps.last().unwrap().1, steps.len() - 1);
+    }
+
+    #[test]
+    fn test_run_if_efficient() {
+        let open = GridMap::new(6, 5);
+        let path = AStar::run_if_efficient(&open, (0, 2), (5, 2), 1.0).unwrap();
+        assert_eq!(path.len(), 6);
+
+        // two walls with their gaps at opposite ends, 5 straight steps become 13
+        let maze = GridMap::builder(6, 5)
+            .block_all([
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (2, 3),
+                (4, 1),
+                (4, 2),
+                (4, 3),
+                (4, 4),
+            ])
+            .build();
+        assert_eq!(AStar::shortest_cost(&maze, (0, 2), (5, 2)), Some(13));
+        assert_eq!(AStar::run_if_efficient(&maze, (0, 2), (5, 2), 2.0), None);
+        assert!(AStar::run_if_efficient(&maze, (0, 2), (5, 2), 3.0).is_some());
+    }
+}
@@ -0,0 +1,115 @@
+use crate::sweep::sweep;
+use crate::{AStar, Cost, PathGenerator};
+use std::rc::Rc;
+
+// walks the edges of inner backwards, the heuristic is expected to be symmetric
+pub(crate) struct Reversed<'a, T> {
+    pub(crate) inner: &'a T,
+}
+
+impl<C: Cost, T: PathGenerator<C>> PathGenerator<C> for Reversed<'_, T> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner.reverse_neighbors(from_position)
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.inner.calculate_cost(next_position, current_position)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        self.inner.calculate_heuristic_cost(position, target)
+    }
+
+    fn reverse_neighbors(&self, to_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner.generate_paths(to_position)
+    }
+}
+
+impl<C: Cost> AStar<C> {
+    // searches from goal toward start over reverse_neighbors, the path is ordered from start to goal
+    pub fn run_reverse<T: PathGenerator<C>>(
+        from_struct: &T,
+        goal: (usize, usize),
+        start: (usize, usize),
+    ) -> Option<Vec<(usize, usize)>> {
+        let reversed = Reversed { inner: from_struct };
+        AStar::run(&reversed, goal, (Some(start.0), Some(start.1)))
+    }
+
+    // cheapest path from start over one of pickups to goal ordered like in run, one sweep
+    // from start and one backwards from goal, equal detours go to the earlier pickup
+    pub fn run_via_one<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        pickups: &[(usize, usize)],
+        goal: (usize, usize),
+    ) -> Option<Vec<(usize, usize)>> {
+        let to_pickups = sweep(from_struct, start, Some(pickups));
+        let from_pickups = sweep(&Reversed { inner: from_struct }, goal, Some(pickups));
+        let (to_pickup, from_pickup) = pickups
+            .iter()
+            .filter_map(|pickup| Some((to_pickups.get(pickup)?, from_pickups.get(pickup)?)))
+            .min_by_key(|(to_pickup, from_pickup)| to_pickup.cost + from_pickup.cost)?;
+        // walked backwards from goal the second leg already runs from the pickup to goal
+        let mut path = Self::reconstruct_path(Rc::clone(from_pickup));
+        path.reverse();
+        path.extend(&Self::reconstruct_path(Rc::clone(to_pickup))[1..]);
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::Graph;
+    use crate::{AStar, GridMap, PathGenerator};
+
+    fn path_cost(graph: &Graph, path: &[(usize, usize)]) -> usize {
+        path.windows(2)
+            .map(|pair| graph.calculate_cost(pair[0], pair[1]))
+            .sum()
+    }
+
+    #[test]
+    fn test_run_reverse_on_directed_graph() {
+        // 3 -> 0 is only usable forward from 3, the reverse search must not walk it from 0
+        let graph = Graph::new(vec![
+            (0, 1, 2),
+            (1, 2, 2),
+            (0, 2, 5),
+            (2, 3, 1),
+            (3, 0, 1),
+            (1, 3, 4),
+        ]);
+        let mut forward = AStar::run(&graph, (0, 0), (Some(3), Some(0))).unwrap();
+        forward.reverse();
+        let reverse = AStar::run_reverse(&graph, (3, 0), (0, 0)).unwrap();
+        assert_eq!(reverse, forward);
+        assert_eq!(reverse, vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+        assert_eq!(path_cost(&graph, &reverse), 5);
+        assert_eq!(AStar::run_reverse(&graph, (0, 0), (3, 0)).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_run_via_one() {
+        let map = GridMap::new(7, 4);
+        // (3, 3) costs a 6 step detour, (1, 1) costs 2
+        let pickups = [(3, 3), (1, 1)];
+        let path = AStar::run_via_one(&map, (0, 0), &pickups, (6, 0)).unwrap();
+        assert_eq!(path.first(), Some(&(6, 0)));
+        assert_eq!(path.last(), Some(&(0, 0)));
+        assert!(path.contains(&(1, 1)) && !path.contains(&(3, 3)));
+        assert_eq!(path.len(), 9);
+        for pair in path.windows(2) {
+            assert!(map.generate_paths(pair[1]).contains(&pair[0]));
+        }
+        assert_eq!(AStar::run_via_one(&map, (0, 0), &[], (6, 0)), None);
+
+        let graph = Graph::new(vec![(0, 1, 1), (1, 2, 1), (0, 3, 5), (3, 2, 5)]);
+        let path = AStar::run_via_one(&graph, (0, 0), &[(3, 0)], (2, 0)).unwrap();
+        assert_eq!(path, vec![(2, 0), (3, 0), (0, 0)]);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{AStar, Cost, PathGenerator};
+
+// inner without its heuristic, routes reaching bound or more are dropped
+struct Unguided<'a, T, C> {
+    inner: &'a T,
+    bound: Option<C>,
+}
+
+impl<C: Cost, T: PathGenerator<C>> PathGenerator<C> for Unguided<'_, T, C> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner.generate_paths(from_position)
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.inner.calculate_cost(current_position, next_position)
+    }
+
+    #[allow(unused_variables)]
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        C::default()
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        self.bound.is_some_and(|bound| g_cost >= bound) || self.inner.prune(position, g_cost)
+    }
+}
+
+impl<C: Cost> AStar<C> {
+    // searches with the heuristic of from_struct, then checks with a search without it that no
+    // cheaper path exists, only routes cheaper than the one found are followed by that check.
+    // The path is optimal whatever the heuristic, the flag is true when the heuristic one wasn't
+    pub fn run_safe<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> Option<(Vec<(usize, usize)>, bool)> {
+        let mut inst = Self::new(target);
+        let guided = inst.search(from_struct, start);
+        let unguided = Unguided {
+            inner: from_struct,
+            bound: guided.as_ref().map(|goal| goal.cost),
+        };
+        match AStar::run(&unguided, start, target) {
+            Some(path) => Some((path, true)),
+            None => guided.map(|goal| (Self::reconstruct_path(goal), false)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::Graph;
+    use crate::{AStar, GridMap, PathGenerator};
+    use std::collections::HashMap;
+
+    fn path_cost(graph: &Graph, path: &[(usize, usize)]) -> usize {
+        path.windows(2)
+            .map(|pair| graph.calculate_cost(pair[1], pair[0]))
+            .sum()
+    }
+
+    #[test]
+    fn test_run_safe() {
+        // 1 claims to be 100 away from the goal although it's on the cheapest route
+        let mut graph = Graph::new(vec![(0, 1, 1), (1, 3, 1), (0, 2, 3), (2, 3, 3)]);
+        graph.heuristic = HashMap::from([(0, 2), (1, 100), (2, 3)]);
+        let target = (Some(3), Some(0));
+        let unsafe_path = AStar::run(&graph, (0, 0), target).unwrap();
+        assert_eq!(path_cost(&graph, &unsafe_path), 6);
+
+        let (path, fell_back) = AStar::run_safe(&graph, (0, 0), target).unwrap();
+        assert!(fell_back);
+        assert_eq!(path, vec![(3, 0), (1, 0), (0, 0)]);
+        assert_eq!(path_cost(&graph, &path), 2);
+
+        let map = GridMap::new(5, 5);
+        let (path, fell_back) = AStar::run_safe(&map, (0, 0), (Some(4), Some(4))).unwrap();
+        assert!(!fell_back);
+        assert_eq!(path.len(), 9);
+
+        // the overestimate is on a cell far from where the heuristic search went
+        let mut graph = Graph::new(vec![(0, 1, 1), (1, 2, 1), (2, 5, 1), (0, 3, 2), (3, 5, 2)]);
+        graph.heuristic = HashMap::from([(1, 50), (2, 1), (3, 2)]);
+        let target = (Some(5), Some(0));
+        assert_eq!(
+            path_cost(&graph, &AStar::run(&graph, (0, 0), target).unwrap()),
+            4
+        );
+        let (path, fell_back) = AStar::run_safe(&graph, (0, 0), target).unwrap();
+        assert!(fell_back);
+        assert_eq!(path_cost(&graph, &path), 3);
+        assert!(AStar::run_safe(&graph, (5, 0), (Some(0), Some(0))).is_none());
+    }
+}
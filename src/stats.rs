@@ -0,0 +1,151 @@
+use crate::{AStar, Cost, PathGenerator};
+#[cfg(feature = "profiling")]
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SearchStats {
+    pub expanded: usize,
+    pub generated: usize,
+    pub reopened: usize,
+    // edges dropped for exceeding the edge time budget
+    pub slow_edges: usize,
+    // number of steps in the found path, 0 if there is none
+    pub path_len: usize,
+    // path_len / expanded, close to 1.0 when the heuristic leads straight to the target
+    pub goal_directedness: f64,
+    // (expanded - path_len) / expanded, the share of expansions off the path, 0.0 when the
+    // heuristic leads straight to the target
+    pub waste_ratio: f64,
+    #[cfg(feature = "profiling")]
+    pub time_in_ordering: Duration,
+    #[cfg(feature = "profiling")]
+    pub time_in_expansion: Duration,
+}
+
+impl<C: Cost> AStar<C> {
+    pub fn run_with_stats<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> (Option<Vec<(usize, usize)>>, SearchStats) {
+        let mut inst = Self::new(target);
+        let path = inst.search(from_struct, start).map(Self::reconstruct_path);
+        let stats = inst.stats(path.as_ref());
+        (path, stats)
+    }
+
+    pub(crate) fn stats(&self, path: Option<&Vec<(usize, usize)>>) -> SearchStats {
+        let path_len = path.map_or(0, |path| path.len() - 1);
+        let (goal_directedness, waste_ratio) = if self.expanded == 0 {
+            (0.0, 0.0)
+        } else {
+            (
+                path_len as f64 / self.expanded as f64,
+                self.expanded.saturating_sub(path_len) as f64 / self.expanded as f64,
+            )
+        };
+        SearchStats {
+            expanded: self.expanded,
+            generated: self.generated,
+            reopened: self.reopened,
+            slow_edges: self.slow_edges,
+            path_len,
+            goal_directedness,
+            waste_ratio,
+            #[cfg(feature = "profiling")]
+            time_in_ordering: self.time_in_ordering,
+            #[cfg(feature = "profiling")]
+            time_in_expansion: self.time_in_expansion,
+        }
+    }
+}
+
+// b* of N + 1 = 1 + b* + b*^2 + ... + b*^depth with N = stats.generated, found by bisection.
+// 1.0 means every generated node was on a path of depth steps, lower than 1 needs fewer
+// generated nodes than the depth which no search produces
+pub fn effective_branching_factor(stats: &SearchStats, solution_depth: usize) -> f64 {
+    let nodes = stats.generated as f64 + 1.0;
+    let tree_size = |branching: f64| {
+        (0..=solution_depth)
+            .map(|level| branching.powi(level as i32))
+            .sum::<f64>()
+    };
+    let (mut low, mut high) = (0.0, nodes);
+    for _ in 0..100 {
+        let middle = (low + high) / 2.0;
+        if tree_size(middle) < nodes {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / 2.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_effective_branching_factor() {
+        let map = GridMap::builder(12, 12)
+            .block_all([(5, 3), (5, 4), (5, 5), (5, 6), (5, 7)])
+            .build();
+        let (_, stats) = AStar::run_with_stats(&map, (2, 5), (Some(9), Some(5)));
+        let branching = effective_branching_factor(&stats, stats.path_len);
+        assert!(branching > 1.0 && branching < 4.0);
+
+        let generated = |generated| SearchStats {
+            generated,
+            ..SearchStats::default()
+        };
+        // 1 + 2 + 4 + 8 nodes
+        assert!((effective_branching_factor(&generated(14), 3) - 2.0).abs() < 1e-9);
+        assert!((effective_branching_factor(&generated(3), 3) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_goal_directedness() {
+        let mut map = GridMap::new(8, 5);
+        let (path, stats) = AStar::run_with_stats(&map, (0, 2), (Some(7), Some(2)));
+        assert_eq!(path.unwrap().len(), 8);
+        assert_eq!(stats.path_len, 7);
+        assert_eq!(stats.goal_directedness, 1.0);
+
+        map.extend([(4, 1), (4, 2), (4, 3)]);
+        let (path, stats) = AStar::run_with_stats(&map, (0, 2), (Some(7), Some(2)));
+        assert!(path.is_some());
+        assert!(stats.goal_directedness < 1.0);
+        assert!(stats.generated >= stats.expanded);
+    }
+
+    #[test]
+    fn test_waste_ratio() {
+        let mut map = GridMap::new(8, 5);
+        let (_, stats) = AStar::run_with_stats(&map, (0, 2), (Some(7), Some(2)));
+        assert_eq!(stats.waste_ratio, 0.0);
+
+        map.extend([(4, 1), (4, 2), (4, 3)]);
+        let (_, stats) = AStar::run_with_stats(&map, (0, 2), (Some(7), Some(2)));
+        assert!(stats.waste_ratio > 0.0 && stats.waste_ratio < 1.0);
+        let off_path = (stats.expanded - stats.path_len) as f64 / stats.expanded as f64;
+        assert_eq!(stats.waste_ratio, off_path);
+    }
+
+    #[cfg(feature = "profiling")]
+    #[test]
+    fn test_profiling_timings() {
+        use std::time::Instant;
+
+        // a coarse clock may measure any of the spans as zero, they can't add up to more than
+        // the whole search though
+        let map = GridMap::new(30, 30);
+        let started = Instant::now();
+        let (path, stats) = AStar::run_with_stats(&map, (0, 0), (Some(29), Some(29)));
+        let elapsed = started.elapsed();
+        assert!(path.is_some());
+        assert!(stats.expanded > 0);
+        assert!(stats.time_in_ordering + stats.time_in_expansion <= elapsed);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{Cost, PathGenerator};
+
+// a width x height window of inner with its top left corner at origin, positions are local
+// to the window and nothing of inner is copied
+pub struct SubGrid<'a, G> {
+    inner: &'a G,
+    origin: (usize, usize),
+    width: usize,
+    height: usize,
+}
+
+impl<'a, G> SubGrid<'a, G> {
+    pub fn new(inner: &'a G, origin: (usize, usize), width: usize, height: usize) -> Self {
+        Self {
+            inner,
+            origin,
+            width,
+            height,
+        }
+    }
+
+    pub fn to_inner(&self, position: (usize, usize)) -> (usize, usize) {
+        (position.0 + self.origin.0, position.1 + self.origin.1)
+    }
+
+    // None for positions of inner outside of the window
+    pub fn from_inner(&self, position: (usize, usize)) -> Option<(usize, usize)> {
+        let x = position.0.checked_sub(self.origin.0)?;
+        let y = position.1.checked_sub(self.origin.1)?;
+        if x < self.width && y < self.height {
+            return Some((x, y));
+        }
+        None
+    }
+}
+
+impl<G: PathGenerator<C>, C: Cost> PathGenerator<C> for SubGrid<'_, G> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner
+            .generate_paths(self.to_inner(from_position))
+            .into_iter()
+            .filter_map(|position| self.from_inner(position))
+            .collect()
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.inner.calculate_cost(
+            self.to_inner(current_position),
+            self.to_inner(next_position),
+        )
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        let target = (
+            target.0.map(|x| x + self.origin.0),
+            target.1.map(|y| y + self.origin.1),
+        );
+        self.inner
+            .calculate_heuristic_cost(self.to_inner(position), target)
+    }
+
+    fn reverse_neighbors(&self, to_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner
+            .reverse_neighbors(self.to_inner(to_position))
+            .into_iter()
+            .filter_map(|position| self.from_inner(position))
+            .collect()
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        self.inner.prune(self.to_inner(position), g_cost)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_matches_cropped_map() {
+        let walls = [(8, 5), (8, 6), (8, 7), (8, 8), (2, 2), (11, 9), (7, 10)];
+        let map = GridMap::builder(20, 20).block_all(walls).build();
+        let window = SubGrid::new(&map, (5, 4), 8, 6);
+
+        let cropped = GridMap::builder(8, 6)
+            .block_all(walls.iter().filter_map(|wall| window.from_inner(*wall)))
+            .build();
+        let target = (Some(7), Some(2));
+        let path = AStar::run(&window, (0, 2), target);
+        assert_eq!(path, AStar::run(&cropped, (0, 2), target));
+        // on the full map the path may leave the window, here it has to go around the wall
+        let path = path.unwrap();
+        assert_eq!(path.len(), 12);
+        assert!(path
+            .iter()
+            .all(|position| !map.is_blocked(window.to_inner(*position))));
+    }
+}
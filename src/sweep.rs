@@ -0,0 +1,354 @@
+use crate::{AStar, Cost, Node, PathGenerator};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::rc::Rc;
+
+// Dijkstra expansion from start, each settled position keeps the node it was settled with.
+// Stops once every position in until is settled or nothing else is reachable,
+// without until everything reachable is settled.
+pub(crate) fn sweep<C: Cost, T: PathGenerator<C>>(
+    from_struct: &T,
+    start: (usize, usize),
+    until: Option<&[(usize, usize)]>,
+) -> HashMap<(usize, usize), Rc<Node<C>>> {
+    let mut pending: Option<HashSet<(usize, usize)>> =
+        until.map(|until| until.iter().copied().collect());
+    if until.is_some_and(|until| until.is_empty()) {
+        return HashMap::new();
+    }
+    sweep_with(from_struct, start, |top| match pending.as_mut() {
+        Some(pending) => {
+            pending.remove(&top.position);
+            !pending.is_empty()
+        }
+        None => true,
+    })
+}
+
+// sweep handing every node to on_settled as it's settled, in order of cost, the expansion
+// stops once on_settled returns false
+fn sweep_with<C: Cost, T: PathGenerator<C>>(
+    from_struct: &T,
+    start: (usize, usize),
+    mut on_settled: impl FnMut(&Rc<Node<C>>) -> bool,
+) -> HashMap<(usize, usize), Rc<Node<C>>> {
+    let mut settled: HashMap<(usize, usize), Rc<Node<C>>> = HashMap::new();
+    let mut que = BinaryHeap::new();
+    que.push(Reverse(Node::new(start, C::default())));
+    while let Some(Reverse(node)) = que.pop() {
+        if settled.contains_key(&node.position) {
+            continue;
+        }
+        let top = Rc::new(node);
+        settled.insert(top.position, Rc::clone(&top));
+        if !on_settled(&top) {
+            break;
+        }
+        for possible_path in from_struct.generate_paths(top.position) {
+            if settled.contains_key(&possible_path) {
+                continue;
+            }
+            let edge_cost = from_struct.calculate_cost(top.position, possible_path);
+            if edge_cost.is_infinite() {
+                continue;
+            }
+            let cost = top.cost + edge_cost;
+            que.push(Reverse(Node {
+                position: possible_path,
+                cost,
+                total_cost: cost,
+                tie_break: 0,
+                drawn_tie_break: 0,
+                comes_from: Some(Rc::clone(&top)),
+            }));
+        }
+    }
+    settled
+}
+
+impl<C: Cost> AStar<C> {
+    // single expansion from start shared by all targets, paths are ordered like in run
+    pub fn paths_to_many<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        targets: &[(usize, usize)],
+    ) -> Vec<Option<Vec<(usize, usize)>>> {
+        let settled = sweep(from_struct, start, Some(targets));
+        targets
+            .iter()
+            .map(|target| {
+                settled
+                    .get(target)
+                    .map(|node| Self::reconstruct_path(Rc::clone(node)))
+            })
+            .collect()
+    }
+
+    // row i holds the costs from nodes[i] to every node, one sweep per row
+    pub fn all_pairs<T: PathGenerator<C>>(
+        from_struct: &T,
+        nodes: &[(usize, usize)],
+    ) -> Vec<Vec<Option<C>>> {
+        nodes
+            .iter()
+            .map(|start| {
+                let settled = sweep(from_struct, *start, Some(nodes));
+                nodes
+                    .iter()
+                    .map(|node| settled.get(node).map(|node| node.cost))
+                    .collect()
+            })
+            .collect()
+    }
+
+    // cost to reach every reachable position from start
+    pub fn cost_field<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+    ) -> HashMap<(usize, usize), C> {
+        sweep(from_struct, start, None)
+            .into_iter()
+            .map(|(position, node)| (position, node.cost))
+            .collect()
+    }
+
+    // the n cheapest cells matching predicate with their costs, cheapest first, fewer when
+    // not as many are reachable
+    pub fn nearest_n_matching<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        n: usize,
+        predicate: impl Fn((usize, usize)) -> bool,
+    ) -> Vec<((usize, usize), C)> {
+        let mut matching = Vec::new();
+        if n == 0 {
+            return matching;
+        }
+        sweep_with(from_struct, start, |top| {
+            if predicate(top.position) {
+                matching.push((top.position, top.cost));
+            }
+            matching.len() < n
+        });
+        matching
+    }
+
+    // path to the cheapest of targets, equal cost targets are decided by the lowest prefer,
+    // every reachable target is settled to find all the equal ones
+    pub fn run_multi_target_prefer<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        targets: &[(usize, usize)],
+        prefer: impl Fn((usize, usize)) -> usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        let settled = sweep(from_struct, start, Some(targets));
+        let goal = targets
+            .iter()
+            .filter_map(|target| settled.get(target))
+            .min_by_key(|node| (node.cost, prefer(node.position)))?;
+        Some(Self::reconstruct_path(Rc::clone(goal)))
+    }
+
+    // every reachable cell mapped to (index into sources, cost) of its cheapest source,
+    // ties go to the lower index
+    pub fn nearest_source_map<T: PathGenerator<C>>(
+        from_struct: &T,
+        sources: &[(usize, usize)],
+    ) -> HashMap<(usize, usize), (usize, C)> {
+        let mut settled = HashMap::new();
+        let mut que: BinaryHeap<_> = sources
+            .iter()
+            .enumerate()
+            .map(|(index, source)| Reverse((C::default(), index, *source)))
+            .collect();
+        while let Some(Reverse((cost, index, position))) = que.pop() {
+            if settled.contains_key(&position) {
+                continue;
+            }
+            settled.insert(position, (index, cost));
+            for possible_path in from_struct.generate_paths(position) {
+                if settled.contains_key(&possible_path) {
+                    continue;
+                }
+                let edge_cost = from_struct.calculate_cost(position, possible_path);
+                if edge_cost.is_infinite() {
+                    continue;
+                }
+                que.push(Reverse((cost + edge_cost, index, possible_path)));
+            }
+        }
+        settled
+    }
+}
+
+impl AStar {
+    // path to the region cell with the highest desirability - path cost, equal scores go to
+    // the cheaper cell, every reachable cell of region is settled
+    pub fn run_to_soft_region<T: PathGenerator>(
+        from_struct: &T,
+        start: (usize, usize),
+        region: &HashMap<(usize, usize), i64>,
+    ) -> Option<Vec<(usize, usize)>> {
+        let cells: Vec<_> = region.keys().copied().collect();
+        let settled = sweep(from_struct, start, Some(&cells));
+        let goal = cells
+            .iter()
+            .filter_map(|cell| settled.get(cell))
+            .max_by_key(|node| {
+                let score = region[&node.position] - node.cost as i64;
+                (score, Reverse(node.cost))
+            })?;
+        Some(Self::reconstruct_path(Rc::clone(goal)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test::{assert_optimal, Graph};
+    use crate::{AStar, GridMap};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_paths_to_many() {
+        // comb shaped map, every cell is reached by a single shortest path
+        let mut map = GridMap::new(7, 4);
+        for x in [1, 3, 5] {
+            map.extend([(x, 1), (x, 2), (x, 3)]);
+        }
+        let targets = [(2, 3), (6, 3), (4, 1)];
+        let paths = AStar::paths_to_many(&map, (0, 3), &targets);
+        for (target, path) in targets.iter().zip(paths) {
+            let single = AStar::run(&map, (0, 3), (Some(target.0), Some(target.1)));
+            assert_eq!(path, single);
+        }
+
+        map.extend([(6, 0), (6, 1)]);
+        assert_eq!(AStar::paths_to_many(&map, (0, 3), &[(6, 3)]), vec![None]);
+    }
+
+    #[test]
+    fn test_paths_to_many_weighted() {
+        let graph = Graph::new(vec![(0, 4, 10), (0, 1, 1), (1, 2, 1), (2, 3, 1), (3, 4, 1)]);
+        let targets = [(4, 0), (2, 0), (0, 0)];
+        let paths = AStar::paths_to_many(&graph, (0, 0), &targets);
+        assert_eq!(paths[0], Some(vec![(4, 0), (3, 0), (2, 0), (1, 0), (0, 0)]));
+        assert_eq!(paths[2], Some(vec![(0, 0)]));
+        for (target, path) in targets.iter().zip(paths) {
+            let single = AStar::run(&graph, (0, 0), (Some(target.0), Some(target.1)));
+            assert_optimal(&graph, (0, 0), *target, path.as_ref().unwrap());
+            assert_eq!(path, single);
+        }
+        assert!(AStar::paths_to_many(&graph, (0, 0), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_all_pairs() {
+        let map = GridMap::builder(5, 5)
+            .block_all([(1, 1), (2, 1), (3, 1), (2, 3)])
+            .build();
+        let nodes = [(0, 0), (4, 4), (2, 2), (2, 4)];
+        let matrix = AStar::all_pairs(&map, &nodes);
+        for (i, from) in nodes.iter().enumerate() {
+            for (j, to) in nodes.iter().enumerate() {
+                assert_eq!(matrix[i][j], matrix[j][i]);
+                assert_eq!(matrix[i][j], AStar::shortest_cost(&map, *from, *to));
+            }
+            assert_eq!(matrix[i][i], Some(0));
+        }
+    }
+
+    #[test]
+    fn test_run_multi_target_prefer() {
+        let map = GridMap::builder(5, 2).block((3, 0)).build();
+        let targets = [(0, 0), (4, 0), (0, 1)];
+        let near_right = |position: (usize, usize)| position.0.abs_diff(4);
+        let path = AStar::run_multi_target_prefer(&map, (2, 0), &targets, near_right).unwrap();
+        assert_eq!(path.first(), Some(&(0, 0)));
+
+        let map = GridMap::new(5, 2);
+        let path = AStar::run_multi_target_prefer(&map, (2, 0), &targets, near_right).unwrap();
+        assert_eq!(path, vec![(4, 0), (3, 0), (2, 0)]);
+        let near_left = |position: (usize, usize)| position.0;
+        let path = AStar::run_multi_target_prefer(&map, (2, 0), &targets, near_left).unwrap();
+        assert_eq!(path, vec![(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(
+            AStar::run_multi_target_prefer(&map, (2, 0), &[], near_left),
+            None
+        );
+    }
+
+    #[test]
+    fn test_nearest_source_map() {
+        // (3, 0) is closer to source 1 in a straight line, but the wall makes source 0 nearer
+        let map = GridMap::builder(5, 4)
+            .block_all([(3, 1), (4, 1), (2, 1), (1, 1)])
+            .build();
+        let sources = [(0, 0), (4, 2)];
+        let nearest = AStar::nearest_source_map(&map, &sources);
+        assert_eq!(nearest[&(0, 0)], (0, 0));
+        assert_eq!(nearest[&(4, 2)], (1, 0));
+        assert_eq!(nearest[&(3, 0)], (0, 3));
+        assert_eq!(nearest[&(0, 2)], (0, 2));
+        assert_eq!(nearest[&(2, 3)], (1, 3));
+        assert_eq!(nearest.len(), 16);
+        for (position, (_, cost)) in &nearest {
+            let to_source = |source: (usize, usize)| AStar::shortest_cost(&map, source, *position);
+            assert_eq!(
+                Some(*cost),
+                sources.iter().filter_map(|s| to_source(*s)).min()
+            );
+        }
+    }
+
+    #[test]
+    fn test_nearest_source_map_skips_infinite_edges() {
+        let graph = Graph::new(vec![(0, 1, usize::MAX), (0, 2, 1)]);
+        let nearest = AStar::nearest_source_map(&graph, &[(0, 0)]);
+        assert_eq!(nearest.get(&(2, 0)), Some(&(0, 1)));
+        assert!(!nearest.contains_key(&(1, 0)));
+    }
+
+    #[test]
+    fn test_nearest_n_matching() {
+        // the wall makes (3, 0) cost 7 instead of 3
+        let map = GridMap::builder(10, 10).block_all([(2, 0), (2, 1)]).build();
+        let exits = [(9, 9), (0, 5), (3, 0), (4, 4), (9, 0)];
+        let is_exit = |position| exits.contains(&position);
+        assert_eq!(
+            AStar::nearest_n_matching(&map, (0, 0), 3, is_exit),
+            vec![((0, 5), 5), ((3, 0), 7), ((4, 4), 8)]
+        );
+        let all = AStar::nearest_n_matching(&map, (0, 0), 10, is_exit);
+        assert_eq!(all.len(), 5);
+        assert!(all.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert_eq!(all.last(), Some(&((9, 9), 18)));
+        assert!(AStar::nearest_n_matching(&map, (0, 0), 0, is_exit).is_empty());
+    }
+
+    #[test]
+    fn test_run_to_soft_region() {
+        // scores of desirability - cost from (0, 2): -1, 0, 1, 4, 2 along the row and 0 in the
+        // far corner which is the most desirable cell
+        let region = HashMap::from([
+            ((3, 2), 2),
+            ((4, 2), 4),
+            ((5, 2), 6),
+            ((6, 2), 10),
+            ((7, 2), 9),
+            ((9, 4), 11),
+        ]);
+        let map = GridMap::new(10, 5);
+        let path = AStar::run_to_soft_region(&map, (0, 2), &region).unwrap();
+        assert_eq!(path.first(), Some(&(6, 2)));
+        assert_eq!(path.len(), 7);
+
+        // going around the blocked center makes (7, 2) score 0
+        let map = GridMap::builder(10, 5).block((6, 2)).build();
+        let path = AStar::run_to_soft_region(&map, (0, 2), &region).unwrap();
+        assert_eq!(path.first(), Some(&(5, 2)));
+        assert_eq!(
+            AStar::run_to_soft_region(&map, (0, 2), &HashMap::new()),
+            None
+        );
+    }
+}
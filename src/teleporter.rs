@@ -0,0 +1,104 @@
+use crate::{Cost, PathGenerator};
+
+// adds an edge from entrance to exit on top of inner, optionally back as well
+pub struct Teleporter<G, C = usize> {
+    inner: G,
+    entrance: (usize, usize),
+    exit: (usize, usize),
+    cost: C,
+    two_way: bool,
+}
+
+impl<G, C: Cost> Teleporter<G, C> {
+    pub fn new(inner: G, entrance: (usize, usize), exit: (usize, usize), cost: C) -> Self {
+        Self {
+            inner,
+            entrance,
+            exit,
+            cost,
+            two_way: false,
+        }
+    }
+
+    pub fn two_way(mut self) -> Self {
+        self.two_way = true;
+        self
+    }
+
+    fn is_jump(&self, current_position: (usize, usize), next_position: (usize, usize)) -> bool {
+        (current_position == self.entrance && next_position == self.exit)
+            || (self.two_way && current_position == self.exit && next_position == self.entrance)
+    }
+}
+
+impl<G: PathGenerator<C>, C: Cost> PathGenerator<C> for Teleporter<G, C> {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut possible_paths = self.inner.generate_paths(from_position);
+        for (from, to) in [(self.entrance, self.exit), (self.exit, self.entrance)] {
+            if self.is_jump(from, to) && from_position == from && !possible_paths.contains(&to) {
+                possible_paths.push(to);
+            }
+        }
+        possible_paths
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        if self.is_jump(current_position, next_position) {
+            return self.cost;
+        }
+        self.inner.calculate_cost(current_position, next_position)
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        // the estimate through the teleporter keeps the heuristic admissible
+        let mut heuristic_cost = self.inner.calculate_heuristic_cost(position, target);
+        for (from, to) in [(self.entrance, self.exit), (self.exit, self.entrance)] {
+            if self.is_jump(from, to) {
+                let through = self
+                    .inner
+                    .calculate_heuristic_cost(position, (Some(from.0), Some(from.1)))
+                    + self.cost
+                    + self.inner.calculate_heuristic_cost(to, target);
+                heuristic_cost = heuristic_cost.min(through);
+            }
+        }
+        heuristic_cost
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_teleport_jump() {
+        let map = GridMap::new(20, 3);
+        let target = (Some(19), Some(1));
+        let walking = AStar::run_with_step_costs(&map, (0, 1), target).unwrap();
+        assert_eq!(walking.last().unwrap().1, 19);
+
+        let teleporter = Teleporter::new(GridMap::new(20, 3), (1, 1), (18, 1), 2);
+        let steps = AStar::run_with_step_costs(&teleporter, (0, 1), target).unwrap();
+        assert_eq!(steps.last().unwrap().1, 4);
+        let path: Vec<_> = steps.iter().map(|step| step.0).collect();
+        assert!(path
+            .windows(2)
+            .any(|pair| pair[0] == (1, 1) && pair[1] == (18, 1)));
+
+        // one way only, the way back has to be walked
+        let back = AStar::run_with_step_costs(&teleporter, (19, 1), (Some(0), Some(1))).unwrap();
+        assert_eq!(back.last().unwrap().1, 19);
+        let two_way = teleporter.two_way();
+        let back = AStar::run_with_step_costs(&two_way, (19, 1), (Some(0), Some(1))).unwrap();
+        assert_eq!(back.last().unwrap().1, 4);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::PathGenerator;
+use std::collections::HashMap;
+
+// 4-connected grid of terrain labels, labels[y][x], where entering a cell costs the entry of
+// its label in cost_table and cells labelled IMPASSABLE can't be entered
+pub struct TerrainGrid {
+    labels: Vec<Vec<u8>>,
+    cost_table: HashMap<u8, usize>,
+    // cheapest entry of cost_table, keeps the heuristic admissible
+    cheapest_step: usize,
+}
+
+impl TerrainGrid {
+    pub const IMPASSABLE: u8 = u8::MAX;
+
+    pub fn new(labels: Vec<Vec<u8>>, cost_table: HashMap<u8, usize>) -> Self {
+        let width = labels.first().map_or(0, Vec::len);
+        assert!(
+            labels.iter().all(|row| row.len() == width),
+            "every row of a TerrainGrid needs the same length"
+        );
+        assert!(
+            labels
+                .iter()
+                .flatten()
+                .all(|label| *label == Self::IMPASSABLE || cost_table.contains_key(label)),
+            "every label of a TerrainGrid needs a cost"
+        );
+        let cheapest_step = cost_table.values().copied().min().unwrap_or(0);
+        Self {
+            labels,
+            cost_table,
+            cheapest_step,
+        }
+    }
+
+    // None outside of the grid
+    pub fn label_at(&self, position: (usize, usize)) -> Option<u8> {
+        self.labels.get(position.1)?.get(position.0).copied()
+    }
+}
+
+impl PathGenerator for TerrainGrid {
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        let (x, y) = from_position;
+        [
+            x.checked_sub(1).map(|x| (x, y)),
+            y.checked_sub(1).map(|y| (x, y)),
+            Some((x + 1, y)),
+            Some((x, y + 1)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|position| {
+            self.label_at(*position)
+                .is_some_and(|label| label != Self::IMPASSABLE)
+        })
+        .collect()
+    }
+
+    #[allow(unused_variables)]
+    fn calculate_cost(
+        &self,
+        current_position: (usize, usize),
+        next_position: (usize, usize),
+    ) -> usize {
+        let label = self
+            .label_at(next_position)
+            .expect("cost requested for a cell outside of the TerrainGrid");
+        self.cost_table[&label]
+    }
+
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> usize {
+        let distance = target.0.map_or(0, |x| x.abs_diff(position.0))
+            + target.1.map_or(0, |y| y.abs_diff(position.1));
+        distance * self.cheapest_step
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::assert_optimal;
+    use crate::AStar;
+
+    const GRASS: u8 = 0;
+    const SAND: u8 = 1;
+    const ROCK: u8 = TerrainGrid::IMPASSABLE;
+
+    #[test]
+    fn test_grass_is_preferred() {
+        // sand straight across row 1, grass around it on rows 0 and 2
+        let labels = vec![
+            vec![GRASS, GRASS, GRASS, GRASS, GRASS],
+            vec![GRASS, SAND, SAND, SAND, GRASS],
+            vec![ROCK, ROCK, ROCK, ROCK, ROCK],
+        ];
+        let terrain = TerrainGrid::new(labels, HashMap::from([(GRASS, 1), (SAND, 3)]));
+        let path = AStar::run_result(&terrain, (0, 1), (Some(4), Some(1))).unwrap();
+        assert_eq!(path.cost, 6);
+        assert_optimal(&terrain, (0, 1), (4, 1), &path.path);
+        assert!(path
+            .path
+            .iter()
+            .all(|position| terrain.label_at(*position) == Some(GRASS)));
+        assert!(!terrain.generate_paths((2, 1)).contains(&(2, 2)));
+    }
+
+    #[test]
+    fn test_walled_off() {
+        let labels = vec![vec![GRASS, ROCK, GRASS], vec![SAND, ROCK, GRASS]];
+        let terrain = TerrainGrid::new(labels, HashMap::from([(GRASS, 1), (SAND, 3)]));
+        assert!(AStar::run(&terrain, (0, 0), (Some(2), Some(1))).is_none());
+        assert_eq!(terrain.label_at((3, 0)), None);
+    }
+}
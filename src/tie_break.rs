@@ -0,0 +1,38 @@
+// order among nodes with the same total cost, preferred positions of run_coherent still come first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TieBreak {
+    // the node queued first wins, the que is sorted stably so this needs no sequence numbers
+    #[default]
+    Fifo,
+    // the node queued last wins, which dives deeper along the newest branch
+    Lifo,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_fifo() {
+        // (1, 0) is queued before (0, 1), so it's expanded first and the goal is queued through it
+        let map = GridMap::new(2, 2);
+        let builder = AStar::builder().tie_break(TieBreak::Fifo);
+        let path = builder.run(&map, (0, 0), (Some(1), Some(1)));
+        assert_eq!(path, Some(vec![(1, 1), (1, 0), (0, 0)]));
+
+        assert_eq!(AStar::builder().run(&map, (0, 0), (Some(1), Some(1))), path);
+
+        let lifo = AStar::builder().tie_break(TieBreak::Lifo);
+        let path = lifo.run(&map, (0, 0), (Some(1), Some(1)));
+        assert_eq!(path, Some(vec![(1, 1), (0, 1), (0, 0)]));
+
+        let map = GridMap::new(8, 8);
+        let first = builder.run(&map, (0, 0), (Some(7), Some(7)));
+        assert_eq!(
+            AStar::builder().run(&map, (0, 0), (Some(7), Some(7))),
+            first
+        );
+        assert_ne!(lifo.run(&map, (0, 0), (Some(7), Some(7))), first);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{AStar, Cost, PathGenerator};
+use std::collections::HashMap;
+
+// what a search did on the way to its result, for animating or debugging it
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SearchTrace {
+    // ordered like the path returned by run
+    pub path: Option<Vec<(usize, usize)>>,
+    // positions in the order they were popped and expanded
+    pub expansion_order: Vec<(usize, usize)>,
+    // positions in the order they were first queued, starting with start
+    pub discovery_order: Vec<(usize, usize)>,
+    // length of the que right after each expanded position was popped
+    pub frontier_series: Vec<usize>,
+    // times each position was handed out by generate_paths, closed neighbours included
+    pub consideration_counts: HashMap<(usize, usize), usize>,
+}
+
+impl<C: Cost> AStar<C> {
+    pub fn run_traced<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> SearchTrace {
+        let mut inst = Self::new(target);
+        inst.trace = Some(SearchTrace::default());
+        let path = inst.search(from_struct, start).map(Self::reconstruct_path);
+        let mut trace = inst.trace.take().unwrap_or_default();
+        trace.path = path;
+        trace
+    }
+
+    pub(crate) fn trace_discovery(&mut self, position: (usize, usize)) {
+        if let Some(trace) = self.trace.as_mut() {
+            if self.discovered.insert(position) {
+                trace.discovery_order.push(position);
+            }
+        }
+    }
+
+    pub(crate) fn trace_consideration(&mut self, position: (usize, usize)) {
+        if let Some(trace) = self.trace.as_mut() {
+            *trace.consideration_counts.entry(position).or_insert(0) += 1;
+        }
+    }
+
+    pub(crate) fn trace_expansion(&mut self, position: (usize, usize)) {
+        if let Some(trace) = self.trace.as_mut() {
+            trace.expansion_order.push(position);
+            trace.frontier_series.push(self.que.len());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_discovery_order() {
+        let map = GridMap::builder(5, 5)
+            .block_all([(2, 1), (2, 2), (2, 3)])
+            .build();
+        let trace = AStar::run_traced(&map, (0, 2), (Some(4), Some(2)));
+        assert_eq!(trace.discovery_order.first(), Some(&(0, 2)));
+        assert_eq!(trace.expansion_order.first(), Some(&(0, 2)));
+        assert!(trace
+            .expansion_order
+            .iter()
+            .all(|position| trace.discovery_order.contains(position)));
+        // the frontier left in the que was discovered but never expanded
+        assert!(trace.discovery_order.len() > trace.expansion_order.len());
+        assert_eq!(trace.path, AStar::run(&map, (0, 2), (Some(4), Some(2))));
+    }
+
+    #[test]
+    fn test_frontier_series() {
+        let map = GridMap::builder(5, 5)
+            .block_all([(2, 1), (2, 2), (2, 3)])
+            .build();
+        let target = (Some(4), Some(2));
+        let trace = AStar::run_traced(&map, (0, 2), target);
+        let (_, stats) = AStar::run_with_stats(&map, (0, 2), target);
+        assert_eq!(trace.frontier_series.len(), stats.expanded);
+        assert_eq!(trace.frontier_series[0], 0);
+        assert!(trace.frontier_series.iter().any(|length| *length > 1));
+
+        // walled in, the search drains the que, the two left at the last expansion are
+        // duplicates of expanded positions
+        let map = GridMap::builder(5, 5)
+            .block_all((0..5).map(|y| (2, y)))
+            .build();
+        let trace = AStar::run_traced(&map, (0, 2), target);
+        assert_eq!(trace.path, None);
+        assert_eq!(trace.discovery_order.len(), 10);
+        assert_eq!(trace.frontier_series, vec![0, 2, 3, 4, 5, 5, 3, 3, 3, 2]);
+    }
+
+    #[test]
+    fn test_consideration_counts() {
+        // a one cell wide corridor on row 0 into a room with a wall in front of the target
+        let mut map = GridMap::new(12, 5);
+        map.extend((0..7).flat_map(|x| (1..5).map(move |y| (x, y))));
+        map.extend([(9, 0), (9, 1), (9, 2), (9, 3)]);
+        let trace = AStar::run_traced(&map, (0, 0), (Some(11), Some(0)));
+        assert!(trace.path.is_some());
+        let count = |position| {
+            trace
+                .consideration_counts
+                .get(&position)
+                .copied()
+                .unwrap_or(0)
+        };
+        // corridor cells are only seen from the cells before and after them
+        assert!((1..7).all(|x| count((x, 0)) == 2));
+        // the room around the wall is searched from every side
+        assert!((1..4).all(|y| count((8, y)) == 3 && count((10, y)) == 3));
+        assert_eq!(count((9, 2)), 0);
+    }
+}
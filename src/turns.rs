@@ -0,0 +1,120 @@
+use crate::{AStar, Cost, PathGenerator};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+type Direction = (i64, i64);
+// (position, step that led into it, turns taken so far)
+type State = ((usize, usize), Option<Direction>, usize);
+
+fn direction(from: (usize, usize), to: (usize, usize)) -> Direction {
+    (
+        (to.0 as i64 - from.0 as i64).signum(),
+        (to.1 as i64 - from.1 as i64).signum(),
+    )
+}
+
+impl<C: Cost> AStar<C> {
+    // cheapest path changing direction at most max_turns times, the first step is free to go
+    // anywhere. Paths are ordered like in run
+    pub fn run_max_turns<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+        max_turns: usize,
+    ) -> Option<Vec<(usize, usize)>> {
+        let mut closed: HashSet<State> = HashSet::new();
+        let mut previous_state: HashMap<State, State> = HashMap::new();
+        let mut que = BinaryHeap::new();
+        let heuristic_cost = from_struct.calculate_heuristic_cost(start, target);
+        que.push(Reverse((
+            heuristic_cost,
+            C::default(),
+            (start, None, 0),
+            None,
+        )));
+        while let Some(Reverse((_, cost, state, previous))) = que.pop() {
+            if !closed.insert(state) {
+                continue;
+            }
+            if let Some(previous) = previous {
+                previous_state.insert(state, previous);
+            }
+            let (position, heading, turns) = state;
+            if Self::target_is_reached(target, &position) {
+                let mut path = vec![position];
+                let mut state = state;
+                while let Some(previous) = previous_state.get(&state) {
+                    path.push(previous.0);
+                    state = *previous;
+                }
+                return Some(path);
+            }
+            for possible_path in from_struct.generate_paths(position) {
+                let step = direction(position, possible_path);
+                let turns = match heading {
+                    Some(heading) if heading != step => turns + 1,
+                    _ => turns,
+                };
+                let next_state = (possible_path, Some(step), turns);
+                if turns > max_turns || closed.contains(&next_state) {
+                    continue;
+                }
+                let edge_cost = from_struct.calculate_cost(position, possible_path);
+                if edge_cost.is_infinite() {
+                    continue;
+                }
+                let new_cost = cost + edge_cost;
+                let total_cost =
+                    new_cost + from_struct.calculate_heuristic_cost(possible_path, target);
+                que.push(Reverse((total_cost, new_cost, next_state, Some(state))));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test::Graph;
+    use crate::GridMap;
+
+    fn turns(path: &[(usize, usize)]) -> usize {
+        path.windows(3)
+            .filter(|cells| direction(cells[0], cells[1]) != direction(cells[1], cells[2]))
+            .count()
+    }
+
+    #[test]
+    fn test_run_max_turns() {
+        // S.#.#G
+        // .#....
+        // ...##.
+        // ......
+        let map = GridMap::builder(6, 4)
+            .block_all([(2, 0), (4, 0), (1, 1), (3, 2), (4, 2)])
+            .build();
+        let target = (Some(5), Some(0));
+        let shortest = AStar::run(&map, (0, 0), target).unwrap();
+        assert_eq!((shortest.len(), turns(&shortest)), (10, 4));
+        assert_eq!(
+            AStar::run_max_turns(&map, (0, 0), target, 4),
+            Some(shortest)
+        );
+
+        // around the bottom row instead of weaving through the middle
+        let path = AStar::run_max_turns(&map, (0, 0), target, 2).unwrap();
+        assert_eq!((path.len(), turns(&path)), (12, 2));
+        assert!(path.contains(&(2, 3)));
+        assert_eq!(AStar::run_max_turns(&map, (0, 0), target, 0), None);
+    }
+
+    #[test]
+    fn test_infinite_edge_is_skipped() {
+        let graph = Graph::new(vec![(0, 1, usize::MAX), (0, 2, 1)]);
+        assert_eq!(
+            AStar::run_max_turns(&graph, (0, 0), (Some(1), Some(0)), 1),
+            None
+        );
+    }
+}
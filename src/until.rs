@@ -0,0 +1,88 @@
+use crate::{AStar, Cost, PathGenerator};
+
+// inner with its heuristic replaced, for goals that aren't a target position
+struct OwnHeuristic<'a, T, H> {
+    inner: &'a T,
+    heuristic: H,
+}
+
+impl<C: Cost, T: PathGenerator<C>, H: Fn((usize, usize)) -> C> PathGenerator<C>
+    for OwnHeuristic<'_, T, H>
+{
+    fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+        self.inner.generate_paths(from_position)
+    }
+
+    fn calculate_cost(&self, current_position: (usize, usize), next_position: (usize, usize)) -> C {
+        self.inner.calculate_cost(current_position, next_position)
+    }
+
+    #[allow(unused_variables)]
+    fn calculate_heuristic_cost(
+        &self,
+        position: (usize, usize),
+        target: (Option<usize>, Option<usize>),
+    ) -> C {
+        (self.heuristic)(position)
+    }
+
+    fn prune(&self, position: (usize, usize), g_cost: C) -> bool {
+        self.inner.prune(position, g_cost)
+    }
+}
+
+impl<C: Cost> AStar<C> {
+    // ends on the first popped position that satisfies predicate and cost at least min_cost
+    // to reach, heuristic estimates the cost left to such a position. Every position is only
+    // popped once at its cheapest cost, so one closer than min_cost is never the goal even if
+    // a detour could make it expensive enough. Paths are ordered like in run
+    pub fn run_until_with_min_cost<T: PathGenerator<C>>(
+        from_struct: &T,
+        start: (usize, usize),
+        mut predicate: impl FnMut((usize, usize)) -> bool,
+        heuristic: impl Fn((usize, usize)) -> C,
+        min_cost: C,
+    ) -> Option<Vec<(usize, usize)>> {
+        let wrapped = OwnHeuristic {
+            inner: from_struct,
+            heuristic,
+        };
+        let mut inst = Self::new((None, None));
+        let goal = inst.search_with(
+            &wrapped,
+            start,
+            || 0,
+            |node| node.cost >= min_cost && predicate(node.position),
+        )?;
+        Some(Self::reconstruct_path(goal))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_run_until_with_min_cost() {
+        let map = GridMap::builder(9, 3).block((4, 1)).build();
+        let cover = [(2, 1), (5, 0), (8, 1)];
+        let is_cover = |position| cover.contains(&position);
+        let start = (1, 1);
+
+        let path = AStar::run_until_with_min_cost(&map, start, is_cover, |_| 0, 0).unwrap();
+        assert_eq!(path, vec![(2, 1), (1, 1)]);
+
+        // (2, 1) is too close, (5, 0) is the nearest cover at least 5 steps away
+        let path = AStar::run_until_with_min_cost(&map, start, is_cover, |_| 0, 5).unwrap();
+        assert_eq!(path.first(), Some(&(5, 0)));
+        assert_eq!(path.len() - 1, 5);
+
+        let path = AStar::run_until_with_min_cost(&map, start, is_cover, |_| 0, 6).unwrap();
+        assert_eq!(path.first(), Some(&(8, 1)));
+        assert_eq!(path.len() - 1, 9);
+        assert_eq!(
+            AStar::run_until_with_min_cost(&map, start, is_cover, |_| 0, 20),
+            None
+        );
+    }
+}
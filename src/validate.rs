@@ -0,0 +1,115 @@
+use crate::{Cost, PathGenerator};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneratorWarning {
+    // the position is listed among its own possible paths
+    SelfLoop((usize, usize)),
+    // a position is listed more than once
+    DuplicatePath {
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+    // to does not list from back, expected only for directed maps
+    Asymmetric {
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+    // repeated calls for the same position returned different paths
+    NonDeterministic((usize, usize)),
+}
+
+pub fn validate_generator<C: Cost, T: PathGenerator<C>>(
+    generator: &T,
+    sample_positions: &[(usize, usize)],
+) -> Vec<GeneratorWarning> {
+    let mut warnings = Vec::new();
+    for position in sample_positions.iter().copied() {
+        let possible_paths = generator.generate_paths(position);
+        if generator.generate_paths(position) != possible_paths {
+            warnings.push(GeneratorWarning::NonDeterministic(position));
+        }
+        for (idx, possible_path) in possible_paths.iter().copied().enumerate() {
+            if possible_path == position {
+                warnings.push(GeneratorWarning::SelfLoop(position));
+                continue;
+            }
+            if possible_paths[..idx].contains(&possible_path) {
+                warnings.push(GeneratorWarning::DuplicatePath {
+                    from: position,
+                    to: possible_path,
+                });
+                continue;
+            }
+            if !generator.generate_paths(possible_path).contains(&position) {
+                warnings.push(GeneratorWarning::Asymmetric {
+                    from: position,
+                    to: possible_path,
+                });
+            }
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::GridMap;
+    use std::cell::Cell;
+
+    struct Buggy {
+        calls: Cell<usize>,
+    }
+
+    impl PathGenerator for Buggy {
+        fn generate_paths(&self, from_position: (usize, usize)) -> Vec<(usize, usize)> {
+            self.calls.set(self.calls.get() + 1);
+            match from_position {
+                (0, 0) => vec![(0, 0), (1, 0), (1, 0)],
+                (1, 0) => vec![(0, 0), (2, 0)],
+                (2, 0) => vec![(3, self.calls.get() % 2)],
+                _ => Vec::new(),
+            }
+        }
+
+        #[allow(unused_variables)]
+        fn calculate_cost(
+            &self,
+            current_position: (usize, usize),
+            next_position: (usize, usize),
+        ) -> usize {
+            1
+        }
+
+        #[allow(unused_variables)]
+        fn calculate_heuristic_cost(
+            &self,
+            position: (usize, usize),
+            target: (Option<usize>, Option<usize>),
+        ) -> usize {
+            0
+        }
+    }
+
+    #[test]
+    fn test_buggy_generator_warnings() {
+        let buggy = Buggy {
+            calls: Cell::new(0),
+        };
+        let warnings = validate_generator(&buggy, &[(0, 0), (1, 0), (2, 0)]);
+        assert!(warnings.contains(&GeneratorWarning::SelfLoop((0, 0))));
+        assert!(warnings.contains(&GeneratorWarning::DuplicatePath {
+            from: (0, 0),
+            to: (1, 0)
+        }));
+        assert!(warnings.contains(&GeneratorWarning::Asymmetric {
+            from: (1, 0),
+            to: (2, 0)
+        }));
+        assert!(warnings.contains(&GeneratorWarning::NonDeterministic((2, 0))));
+        assert!(!warnings.contains(&GeneratorWarning::NonDeterministic((0, 0))));
+
+        let map = GridMap::builder(3, 3).block((1, 1)).build();
+        assert!(validate_generator(&map, &[(0, 0), (1, 0), (2, 2)]).is_empty());
+    }
+}
@@ -0,0 +1,25 @@
+// keeps the order of the path, so the first point is still the target
+pub fn path_to_world(
+    path: &[(usize, usize)],
+    transform: impl Fn((usize, usize)) -> (f32, f32),
+) -> Vec<(f32, f32)> {
+    path.iter().map(|position| transform(*position)).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AStar, GridMap};
+
+    #[test]
+    fn test_path_to_world() {
+        let map = GridMap::new(3, 1);
+        let path = AStar::run(&map, (0, 0), (Some(2), Some(0))).unwrap();
+        let cell_center = |(x, y): (usize, usize)| (x as f32 + 0.5, y as f32 + 0.5);
+        assert_eq!(
+            path_to_world(&path, cell_center),
+            vec![(2.5, 0.5), (1.5, 0.5), (0.5, 0.5)]
+        );
+        assert!(path_to_world(&[], cell_center).is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+use a_star_traitbased::{AStar, ConstGrid, PathGenerator};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// counts the allocations of the current thread only, the harness keeps its own threads busy
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+fn test_const_grid_stays_off_the_heap() {
+    let before = allocations();
+    let mut grid = ConstGrid::<8, 8>::new();
+    for y in 0..7 {
+        grid.block((3, y));
+    }
+    grid.unblock((3, 0));
+    grid.block((3, 0));
+    let copy = grid;
+    assert!(copy.is_blocked((3, 6)) && !copy.is_blocked((3, 7)));
+    assert!(!copy.contains((9, 0)));
+    assert_eq!(copy.calculate_cost((3, 7), (4, 7)), 1);
+    assert_eq!(copy.calculate_heuristic_cost((0, 0), (Some(7), Some(0))), 7);
+    assert_eq!(allocations(), before);
+
+    // the open list, the closed nodes and generate_paths live on the heap
+    let path = AStar::run(&grid, (0, 0), (Some(7), Some(0))).unwrap();
+    assert_eq!(path.len(), 22);
+    assert!(allocations() > before);
+}